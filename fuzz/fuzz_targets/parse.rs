@@ -0,0 +1,36 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sbd_lib::decoder::{Decoded, Decoder};
+use sbd_lib::message_ref::InformationElementRef;
+use sbd_lib::{DirectIpMessage, InformationElement, Message, MessageRef, ParseOptions};
+
+fn check(message: &Message) {
+    let _ = message.imei().to_string();
+    let _ = message.to_string();
+    let mut buff = vec![];
+    let _ = message.write_to(&mut buff);
+}
+
+fuzz_target!(|data: &[u8]| {
+    for options in &[ParseOptions::lenient(), ParseOptions::strict()] {
+        if let Ok(message) = Message::read_from_with(data, *options) {
+            check(&message);
+        }
+        if let Ok(message) = DirectIpMessage::read_from_with(data, *options) {
+            let _ = message.to_string();
+            let _ = message.write_to(&mut vec![]);
+        }
+        if let Ok(message) = MessageRef::parse_with(data, *options) {
+            check(&message.to_owned());
+        }
+        let _ = InformationElementRef::parse_with(data, *options);
+        let _ = InformationElement::read_single_with(data, *options);
+    }
+
+    let mut decoder = Decoder::new();
+    decoder.push(data);
+    while let Ok(Decoded::Message(message)) = decoder.decode() {
+        check(&message);
+    }
+});
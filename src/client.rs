@@ -0,0 +1,205 @@
+use crate::{mt, DirectIpMessage, Error, Message, Result};
+use std::{
+    convert::TryFrom,
+    io::{self, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// A DirectIP client sending mobile-terminated messages to the Iridium gateway.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sbd_lib::client::MtClient;
+/// use sbd_lib::{mt, Message};
+/// use std::time::Duration;
+///
+/// let client = MtClient::new("12.47.179.12:10800")
+///     .unwrap()
+///     .connect_timeout(Some(Duration::from_secs(10)))
+///     .write_timeout(Some(Duration::from_secs(30)))
+///     .read_timeout(Some(Duration::from_secs(30)));
+/// let header = mt::Header {
+///     message_id: 1,
+///     imei: "300434060009290".parse().unwrap(),
+///     flags: mt::DispositionFlags::empty(),
+/// };
+/// let message = Message::new(header.into(), b"hello".to_vec(), None, vec![]);
+/// let confirmation = client.send(&message).unwrap();
+/// println!("{}", confirmation.status);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MtClient {
+    addrs: Vec<SocketAddr>,
+    connect_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+}
+
+impl MtClient {
+    /// Creates a new client for the gateway at `addr`.
+    pub fn new<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        Ok(MtClient {
+            addrs: addr.to_socket_addrs()?.collect(),
+            connect_timeout: None,
+            write_timeout: None,
+            read_timeout: None,
+        })
+    }
+
+    /// Sets the timeout for connecting to the gateway.
+    pub fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the timeout for sending the message.
+    pub fn write_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.write_timeout = timeout;
+        self
+    }
+
+    /// Sets the timeout for reading the confirmation.
+    pub fn read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// Sends a mobile-terminated message and returns the gateway's confirmation.
+    ///
+    /// The message is checked like `MtMessage` does before connecting, so e.g. a payload larger
+    /// than the gateway accepts fails here. The confirmation is returned even when its status
+    /// reports an error.
+    pub fn send(&self, message: &Message) -> Result<mt::ConfirmationStatus> {
+        mt::MtMessage::try_from(message.clone())?;
+        let mut buff = Vec::with_capacity(message.length());
+        message.write_to(&mut buff)?;
+
+        let mut stream = self.connect()?;
+        stream.set_read_timeout(self.read_timeout)?;
+        stream.set_write_timeout(self.write_timeout)?;
+        stream.write_all(&buff)?;
+        stream.flush()?;
+
+        match DirectIpMessage::read_from(stream)? {
+            DirectIpMessage::MtConfirmation(status) => Ok(status),
+            _ => Err(Error::UnexpectedMessage),
+        }
+    }
+
+    fn connect(&self) -> Result<TcpStream> {
+        let timeout = match self.connect_timeout {
+            Some(timeout) => timeout,
+            None => return Ok(TcpStream::connect(self.addrs.as_slice())?),
+        };
+        let mut last_err = None;
+        for addr in &self.addrs {
+            match TcpStream::connect_timeout(addr, timeout) {
+                Ok(stream) => return Ok(stream),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err
+            .unwrap_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "no gateway address to connect to",
+                )
+            })
+            .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mo;
+    use std::{net::TcpListener, thread};
+
+    fn mt_message() -> Message {
+        let header = mt::Header {
+            message_id: 287454020,
+            imei: (*b"300434060009290").into(),
+            flags: mt::DispositionFlags::empty(),
+        };
+        Message::new(header.into(), b"hello".to_vec(), None, vec![])
+    }
+
+    fn gateway(response: Vec<u8>) -> (SocketAddr, thread::JoinHandle<Message>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let message = Message::read_from(&mut stream).unwrap();
+            stream.write_all(&response).unwrap();
+            message
+        });
+        (addr, handle)
+    }
+
+    #[test]
+    fn send() {
+        let (addr, gateway) = gateway(std::fs::read("data/resp.sbd").unwrap());
+        let client = MtClient::new(addr)
+            .unwrap()
+            .connect_timeout(Some(Duration::from_secs(5)))
+            .read_timeout(Some(Duration::from_secs(5)));
+        let confirmation = client.send(&mt_message()).unwrap();
+        assert_eq!(287454020, confirmation.message_id);
+        assert_eq!(3064195606, confirmation.auto_id);
+        assert_eq!(mt::MtStatus::QueuePosition(1), confirmation.status);
+        assert_eq!(mt_message(), gateway.join().unwrap());
+    }
+
+    #[test]
+    fn unexpected_response() {
+        let mut response = vec![];
+        DirectIpMessage::from(mo::ConfirmationStatus::new(true))
+            .write_to(&mut response)
+            .unwrap();
+        let (addr, gateway) = gateway(response);
+        let client = MtClient::new(addr).unwrap();
+        assert!(matches!(
+            client.send(&mt_message()),
+            Err(Error::UnexpectedMessage)
+        ));
+        gateway.join().unwrap();
+    }
+
+    #[test]
+    fn invalid_message() {
+        let client = MtClient::new("127.0.0.1:1").unwrap();
+        let header = mt::Header {
+            message_id: 1,
+            imei: (*b"300434060009290").into(),
+            flags: mt::DispositionFlags::from_bits_retain(0x8000),
+        };
+        assert!(matches!(
+            client.send(&Message::new(
+                header.into(),
+                b"hello".to_vec(),
+                None,
+                vec![]
+            )),
+            Err(Error::ReservedDispositionFlags(0x8000))
+        ));
+        let header = mt::Header {
+            flags: mt::DispositionFlags::empty(),
+            ..header
+        };
+        assert!(matches!(
+            client.send(&Message::new(header.into(), vec![0; 1891], None, vec![])),
+            Err(Error::PayloadTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn mobile_originated_message() {
+        let client = MtClient::new("127.0.0.1:1").unwrap();
+        assert!(matches!(
+            client.send(&Message::from_path("data/0-mo.sbd").unwrap()),
+            Err(Error::UnexpectedMessage)
+        ));
+    }
+}
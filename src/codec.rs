@@ -0,0 +1,124 @@
+use crate::{message_ref::frame_length, Error, Message, MessageRef, Result};
+use bytes::{BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// A `tokio-util` codec framing SBD messages on a DirectIP stream.
+///
+/// Wrap a socket in `Framed::new(stream, SbdCodec::new())` to get a stream and sink of `Message`.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use sbd_lib::codec::SbdCodec;
+/// use tokio_util::codec::Decoder;
+///
+/// let data = std::fs::read("data/0-mo.sbd").unwrap();
+/// let mut buffer = BytesMut::from(&data[..]);
+/// let message = SbdCodec::new().decode(&mut buffer).unwrap().unwrap();
+/// assert_eq!("300234063904190", message.imei());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SbdCodec;
+
+impl SbdCodec {
+    /// Creates a new codec.
+    pub fn new() -> Self {
+        SbdCodec
+    }
+}
+
+impl Decoder for SbdCodec {
+    type Item = Message;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>> {
+        let length = match frame_length(src)? {
+            Some(length) => length,
+            None => return Ok(None),
+        };
+        if src.len() < length {
+            src.reserve(length - src.len());
+            return Ok(None);
+        }
+
+        let frame = src.split_to(length);
+        Ok(Some(MessageRef::parse(&frame)?.to_owned()))
+    }
+}
+
+impl Encoder<Message> for SbdCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<()> {
+        dst.reserve(item.length());
+        item.write_to(dst.writer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_partial() {
+        let data = std::fs::read("data/1-mo-location.sbd").unwrap();
+        let mut codec = SbdCodec::new();
+        let mut buffer = BytesMut::from(&data[..2]);
+        assert_eq!(None, codec.decode(&mut buffer).unwrap());
+        buffer.extend_from_slice(&data[2..20]);
+        assert_eq!(None, codec.decode(&mut buffer).unwrap());
+        buffer.extend_from_slice(&data[20..]);
+        buffer.extend_from_slice(&data[..1]);
+        assert_eq!(
+            Some(Message::from_path("data/1-mo-location.sbd").unwrap()),
+            codec.decode(&mut buffer).unwrap()
+        );
+        assert_eq!(&data[..1], &buffer[..]);
+    }
+
+    #[test]
+    fn decode_mo_without_payload() {
+        let data = std::fs::read("data/iridium.ack").unwrap();
+        let mut buffer = BytesMut::from(&data[..]);
+        let message = SbdCodec::new().decode(&mut buffer).unwrap().unwrap();
+        assert!(!message.has_payload());
+        assert!(message.location().is_some());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn encode() {
+        let message = Message::from_path("data/0-mo.sbd").unwrap();
+        let mut buffer = BytesMut::new();
+        SbdCodec::new().encode(message, &mut buffer).unwrap();
+        assert_eq!(std::fs::read("data/0-mo.sbd").unwrap(), &buffer[..]);
+    }
+
+    #[test]
+    fn encode_too_long() {
+        let message = Message::create(vec![
+            Message::from_path("data/0-mo.sbd")
+                .unwrap()
+                .header()
+                .as_mo()
+                .copied()
+                .unwrap()
+                .into(),
+            crate::InformationElement::MOPayload(vec![0; u16::MAX as usize]),
+        ])
+        .unwrap();
+        let mut buffer = BytesMut::new();
+        assert!(matches!(
+            SbdCodec::new().encode(message, &mut buffer),
+            Err(Error::OverallMessageLength(_))
+        ));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn invalid_protocol_revision_number() {
+        let mut buffer = BytesMut::from(&[2u8, 0, 0][..]);
+        assert!(SbdCodec::new().decode(&mut buffer).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{message_ref::frame_length, Message, MessageRef, Result};
+
+/// The outcome of `Decoder::decode`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Decoded {
+    /// At least this many more bytes are needed before a message can be decoded.
+    NeedMore(usize),
+    /// A complete message.
+    Message(Message),
+}
+
+/// A resumable decoder for SBD messages arriving in arbitrary chunks.
+///
+/// Bytes are buffered with `push` until `decode` finds a complete frame, so it can be driven from
+/// a non-blocking socket.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::decoder::{Decoded, Decoder};
+/// let data = std::fs::read("data/0-mo.sbd").unwrap();
+/// let mut decoder = Decoder::new();
+/// assert_eq!(Decoded::NeedMore(3), decoder.decode().unwrap());
+/// decoder.push(&data[..10]);
+/// assert_eq!(Decoded::NeedMore(49), decoder.decode().unwrap());
+/// decoder.push(&data[10..]);
+/// match decoder.decode().unwrap() {
+///     Decoded::Message(message) => assert_eq!("300234063904190", message.imei()),
+///     Decoded::NeedMore(_) => panic!("Message is complete"),
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Decoder {
+    buffer: Vec<u8>,
+}
+
+impl Decoder {
+    /// Creates a new, empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends received bytes to the decoder.
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Returns the number of buffered bytes not yet decoded.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Decodes the next message from the buffered bytes.
+    ///
+    /// A frame whose information elements fail to parse is dropped, so decoding can carry on with
+    /// the next one. An invalid protocol revision number leaves no way to find the next frame, so
+    /// it discards everything buffered.
+    pub fn decode(&mut self) -> Result<Decoded> {
+        let length = match frame_length(&self.buffer) {
+            Ok(Some(length)) => length,
+            Ok(None) => return Ok(Decoded::NeedMore(Message::HEADER_SIZE - self.buffer.len())),
+            Err(err) => {
+                self.buffer.clear();
+                return Err(err);
+            }
+        };
+        if self.buffer.len() < length {
+            return Ok(Decoded::NeedMore(length - self.buffer.len()));
+        }
+
+        let message = MessageRef::parse(&self.buffer[..length]).map(|m| m.to_owned());
+        self.buffer.drain(..length);
+        Ok(Decoded::Message(message?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_by_byte() {
+        let data = std::fs::read("data/1-mo-location.sbd").unwrap();
+        let mut decoder = Decoder::new();
+        for (i, byte) in data.iter().enumerate() {
+            let expected = if i < Message::HEADER_SIZE {
+                Message::HEADER_SIZE - i
+            } else {
+                data.len() - i
+            };
+            assert_eq!(Decoded::NeedMore(expected), decoder.decode().unwrap());
+            decoder.push(&[*byte]);
+        }
+        assert_eq!(
+            Decoded::Message(Message::from_path("data/1-mo-location.sbd").unwrap()),
+            decoder.decode().unwrap()
+        );
+        assert_eq!(0, decoder.buffered());
+    }
+
+    #[test]
+    fn several_messages() {
+        let first = std::fs::read("data/0-mo.sbd").unwrap();
+        let second = std::fs::read("data/1-mo-location.sbd").unwrap();
+        let mut decoder = Decoder::new();
+        decoder.push(&first);
+        decoder.push(&second[..5]);
+        assert_eq!(
+            Decoded::Message(Message::from_path("data/0-mo.sbd").unwrap()),
+            decoder.decode().unwrap()
+        );
+        assert_eq!(
+            Decoded::NeedMore(second.len() - 5),
+            decoder.decode().unwrap()
+        );
+        decoder.push(&second[5..]);
+        assert_eq!(
+            Decoded::Message(Message::from_path("data/1-mo-location.sbd").unwrap()),
+            decoder.decode().unwrap()
+        );
+    }
+
+    #[test]
+    fn mo_without_payload() {
+        let mut decoder = Decoder::new();
+        decoder.push(&std::fs::read("data/iridium.ack").unwrap());
+        match decoder.decode().unwrap() {
+            Decoded::Message(message) => {
+                assert!(!message.has_payload());
+                assert!(message.location().is_some());
+            }
+            Decoded::NeedMore(_) => panic!("Message is complete"),
+        }
+        assert_eq!(0, decoder.buffered());
+    }
+
+    #[test]
+    fn invalid_frame_is_dropped() {
+        let mut data = std::fs::read("data/resp.sbd").unwrap();
+        data.extend(std::fs::read("data/0-mo.sbd").unwrap());
+        let mut decoder = Decoder::new();
+        decoder.push(&data);
+        assert!(decoder.decode().is_err());
+        assert!(matches!(decoder.decode().unwrap(), Decoded::Message(_)));
+    }
+
+    #[test]
+    fn invalid_protocol_revision_number() {
+        let mut decoder = Decoder::new();
+        decoder.push(&[2, 0, 0]);
+        assert!(decoder.decode().is_err());
+        assert_eq!(0, decoder.buffered());
+    }
+}
@@ -0,0 +1,445 @@
+use crate::{
+    information_element::{Header, InformationElement, ParsedFrame, Status},
+    mo, mt, Message, ParseOptions, Result,
+};
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    io::{Read, Write},
+    path::Path,
+};
+
+const PROTOCOL_REVISION_NUMBER: u8 = 1;
+
+/// Any message exchanged over DirectIP.
+///
+/// Besides mobile-originated and mobile-terminated messages, the gateway sends a lone
+/// confirmation IE in response to an MT message, and expects one in response to an MO message.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+pub enum DirectIpMessage {
+    /// A mobile-originated message.
+    MoMessage(Message),
+    /// A mobile-terminated message.
+    MtMessage(Message),
+    /// The gateway's confirmation of a mobile-terminated message.
+    MtConfirmation(mt::ConfirmationStatus),
+    /// The confirmation of a mobile-originated message.
+    MoConfirmation(mo::ConfirmationStatus),
+}
+
+impl fmt::Display for DirectIpMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirectIpMessage::MoMessage(message) | DirectIpMessage::MtMessage(message) => {
+                write!(f, "{}", message)
+            }
+            DirectIpMessage::MtConfirmation(status) => write!(
+                f,
+                "MT confirmation message_id: {}, imei: {}, auto_id: {}, status: {}",
+                status.message_id, status.imei, status.auto_id, status.status
+            ),
+            DirectIpMessage::MoConfirmation(status) => {
+                write!(f, "MO confirmation status: {}", status.status())
+            }
+        }
+    }
+}
+
+impl DirectIpMessage {
+    /// Creates the confirmation sent back to the gateway for a mobile-originated message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sbd_lib::DirectIpMessage;
+    /// let mut buff = vec![];
+    /// DirectIpMessage::mo_confirmation(true).write_to(&mut buff).unwrap();
+    /// assert_eq!(vec![1, 0, 4, 0x05, 0, 1, 1], buff);
+    /// ```
+    pub fn mo_confirmation(status: bool) -> Self {
+        DirectIpMessage::MoConfirmation(mo::ConfirmationStatus::new(status))
+    }
+
+    /// Create message from Path
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        use std::fs::File;
+        let file = File::open(path)?;
+        Self::read_from(file)
+    }
+
+    /// Create message from Read
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sbd_lib::DirectIpMessage;
+    /// let file = std::fs::File::open("data/resp.sbd").unwrap();
+    /// match DirectIpMessage::read_from(file).unwrap() {
+    ///     DirectIpMessage::MtConfirmation(status) => assert_eq!(287454020, status.message_id),
+    ///     _ => panic!("Unexpected message"),
+    /// }
+    /// ```
+    pub fn read_from<R: Read>(read: R) -> Result<Self> {
+        Self::read_from_with(read, ParseOptions::default())
+    }
+
+    /// Create message from Read with the given parse options
+    pub fn read_from_with<R: Read>(read: R, options: ParseOptions) -> Result<Self> {
+        let frame = InformationElement::read_frame(read)?;
+        let ParsedFrame {
+            information_elements,
+            offsets,
+            skipped,
+        } = InformationElement::parse_frame(&frame, options)?;
+        Ok(
+            match Self::assemble(information_elements, options.is_strict(), Some(&offsets))? {
+                DirectIpMessage::MoMessage(message) => {
+                    DirectIpMessage::MoMessage(message.with_skipped(skipped))
+                }
+                DirectIpMessage::MtMessage(message) => {
+                    DirectIpMessage::MtMessage(message.with_skipped(skipped))
+                }
+                confirmation => confirmation,
+            },
+        )
+    }
+
+    /// Creates a new message from information elements.
+    ///
+    /// The kind of message is picked from the information elements: a header makes it a MO or MT
+    /// message, a single confirmation IE makes it a confirmation. Every information element must
+    /// belong to the same direction as the header.
+    pub fn create<I: IntoIterator<Item = InformationElement>>(iter: I) -> Result<Self> {
+        Self::assemble(iter, true, None)
+    }
+
+    fn assemble<I: IntoIterator<Item = InformationElement>>(
+        iter: I,
+        check_directions: bool,
+        offsets: Option<&[usize]>,
+    ) -> Result<Self> {
+        use crate::Error;
+
+        let information_elements: Vec<_> = iter.into_iter().collect();
+        let header = information_elements.iter().find_map(|ie| match ie {
+            InformationElement::Header(header) => Some(*header),
+            _ => None,
+        });
+
+        match header {
+            Some(Header::MOHeader(_)) => Ok(DirectIpMessage::MoMessage(Message::assemble(
+                information_elements,
+                false,
+                check_directions,
+                offsets,
+            )?)),
+            Some(Header::MTHeader(_)) => Ok(DirectIpMessage::MtMessage(Message::assemble(
+                information_elements,
+                true,
+                check_directions,
+                offsets,
+            )?)),
+            None => match information_elements.as_slice() {
+                [InformationElement::Status(Status::MTStatus(status))] => {
+                    Ok(DirectIpMessage::MtConfirmation(*status))
+                }
+                [InformationElement::Status(Status::MOStatus(status))] => {
+                    Ok(DirectIpMessage::MoConfirmation(*status))
+                }
+                _ if offsets.is_some() => Err(crate::message_ref::message_error(Error::NoHeader)),
+                _ => Err(Error::NoHeader),
+            },
+        }
+    }
+
+    /// Write this message back to a object that can `Write`.
+    pub fn write_to<W: Write>(&self, mut write: W) -> Result<()> {
+        use byteorder::{BigEndian, WriteBytesExt};
+
+        let status = match self {
+            DirectIpMessage::MoMessage(message) | DirectIpMessage::MtMessage(message) => {
+                return message.write_to(write)
+            }
+            DirectIpMessage::MtConfirmation(status) => Status::from(*status),
+            DirectIpMessage::MoConfirmation(status) => Status::from(*status),
+        };
+
+        write.write_u8(PROTOCOL_REVISION_NUMBER)?;
+        write.write_u16::<BigEndian>(status.len() as u16)?;
+        InformationElement::Status(status).write_to(&mut write)
+    }
+
+    /// Write this message back in the order its information elements were read.
+    ///
+    /// See `Message::write_to_original_order`.
+    pub fn write_to_original_order<W: Write>(&self, write: W) -> Result<()> {
+        match self {
+            DirectIpMessage::MoMessage(message) | DirectIpMessage::MtMessage(message) => {
+                message.write_to_original_order(write)
+            }
+            _ => self.write_to(write),
+        }
+    }
+
+    /// Returns the message, if this is a MO or MT message.
+    pub fn as_message(&self) -> Option<&Message> {
+        match self {
+            DirectIpMessage::MoMessage(message) | DirectIpMessage::MtMessage(message) => {
+                Some(message)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<Message> for DirectIpMessage {
+    fn from(message: Message) -> Self {
+        if message.header().as_mo().is_some() {
+            DirectIpMessage::MoMessage(message)
+        } else {
+            DirectIpMessage::MtMessage(message)
+        }
+    }
+}
+
+impl From<mt::ConfirmationStatus> for DirectIpMessage {
+    fn from(status: mt::ConfirmationStatus) -> Self {
+        DirectIpMessage::MtConfirmation(status)
+    }
+}
+
+impl From<mo::ConfirmationStatus> for DirectIpMessage {
+    fn from(status: mo::ConfirmationStatus) -> Self {
+        DirectIpMessage::MoConfirmation(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn mo_message() {
+        let message = DirectIpMessage::from_path("data/0-mo.sbd").unwrap();
+        assert_eq!(
+            DirectIpMessage::MoMessage(Message::from_path("data/0-mo.sbd").unwrap()),
+            message
+        );
+    }
+
+    #[test]
+    fn mo_message_without_payload() {
+        match DirectIpMessage::from_path("data/iridium.ack").unwrap() {
+            DirectIpMessage::MoMessage(message) => {
+                assert_eq!("300434060009290", message.imei());
+                assert!(message.payload().is_empty());
+                assert!(message.location().is_some());
+            }
+            _ => panic!("Unexpected message"),
+        }
+    }
+
+    #[test]
+    fn mt_confirmation() {
+        match DirectIpMessage::from_path("data/resp.sbd").unwrap() {
+            DirectIpMessage::MtConfirmation(status) => {
+                assert_eq!(287454020, status.message_id);
+                assert_eq!(*b"300434060009290", status.imei);
+                assert_eq!(3064195606, status.auto_id);
+                assert_eq!(mt::MtStatus::QueuePosition(1), status.status);
+            }
+            _ => panic!("Unexpected message"),
+        }
+    }
+
+    #[test]
+    fn mt_message() {
+        let header = mt::Header {
+            message_id: 1,
+            imei: (*b"300434060009290").into(),
+            flags: mt::DispositionFlags::empty(),
+        };
+        let message = DirectIpMessage::from(Message::new(header.into(), vec![1, 2], None, vec![]));
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+        assert_eq!(
+            message,
+            DirectIpMessage::read_from(Cursor::new(buff)).unwrap()
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        for path in &["data/0-mo.sbd", "data/iridium.ack", "data/resp.sbd"] {
+            let expected = std::fs::read(path).unwrap();
+            let message = DirectIpMessage::read_from(expected.as_slice()).unwrap();
+            let mut buff = vec![];
+            message.write_to(&mut buff).unwrap();
+            assert_eq!(expected, buff, "{}", path);
+        }
+    }
+
+    #[test]
+    fn roundtrip_original_order() {
+        for entry in std::fs::read_dir("data").unwrap() {
+            let path = entry.unwrap().path();
+            let data = std::fs::read(&path).unwrap();
+            let length = crate::message_ref::frame_length(&data).unwrap().unwrap();
+            let message = DirectIpMessage::read_from(data.as_slice()).unwrap();
+            let mut buff = vec![];
+            message.write_to_original_order(&mut buff).unwrap();
+            assert_eq!(&data[..length], buff.as_slice(), "{}", path.display());
+        }
+    }
+
+    /// Generates frames with information elements in random order, some of them longer than they
+    /// need to be, and checks they are written back byte for byte.
+    #[test]
+    fn roundtrip_generated_original_order() {
+        use crate::MessageRef;
+
+        let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        let information_element = |iei: u8, value: &[u8]| {
+            let mut ie = vec![iei];
+            ie.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            ie.extend_from_slice(value);
+            ie
+        };
+        let mo_header = std::fs::read("data/0-mo.sbd").unwrap()[6..34].to_vec();
+        let mt_confirmation = std::fs::read("data/resp.sbd").unwrap()[6..].to_vec();
+        let mut mt_header = vec![0, 0, 0, 1];
+        mt_header.extend_from_slice(b"300434060009290");
+        mt_header.extend_from_slice(&[0, 0]);
+
+        for _ in 0..2000 {
+            let mobile_originated = random() % 2 == 0;
+            // Fixed-length values that can carry extra bytes, with their identifier.
+            let mut fixed = Vec::new();
+            let mut information_elements = Vec::new();
+            if mobile_originated {
+                fixed.push((0x01, mo_header.clone()));
+                if random() % 2 == 0 {
+                    let flags = random() as u8 & 0xf0;
+                    fixed.push((
+                        0x03,
+                        vec![flags, 43, 0x78, 0x86, 41, 0xbe, 0xdc, 0, 0, 0, 3],
+                    ));
+                }
+                if random() % 4 == 0 {
+                    fixed.push((0x05, vec![1]));
+                }
+            } else {
+                fixed.push((0x41, mt_header.clone()));
+                if random() % 2 == 0 {
+                    fixed.push((0x46, vec![0, random() as u8 % 7]));
+                }
+                if random() % 4 == 0 {
+                    fixed.push((0x44, mt_confirmation.clone()));
+                }
+            }
+            for (iei, mut value) in fixed {
+                for _ in 0..random() % 3 {
+                    value.push(random() as u8);
+                }
+                information_elements.push(information_element(iei, &value));
+            }
+            if !mobile_originated || random() % 2 == 0 {
+                let payload: Vec<u8> = (0..random() % 40).map(|_| random() as u8).collect();
+                let iei = if mobile_originated { 0x02 } else { 0x42 };
+                information_elements.push(information_element(iei, &payload));
+            }
+            for _ in 0..random() % 3 {
+                let data: Vec<u8> = (0..random() % 5).map(|_| random() as u8).collect();
+                information_elements.push(information_element(0x20 + random() as u8 % 16, &data));
+            }
+            for index in (1..information_elements.len()).rev() {
+                information_elements.swap(index, random() as usize % (index + 1));
+            }
+
+            let body = information_elements.concat();
+            let mut data = vec![1];
+            data.extend_from_slice(&(body.len() as u16).to_be_bytes());
+            data.extend_from_slice(&body);
+
+            let messages = [
+                DirectIpMessage::read_from(data.as_slice())
+                    .unwrap()
+                    .as_message()
+                    .unwrap()
+                    .clone(),
+                Message::read_from(data.as_slice()).unwrap(),
+                MessageRef::parse(&data).unwrap().to_owned(),
+            ];
+            for message in &messages {
+                let mut buff = vec![];
+                message.write_to_original_order(&mut buff).unwrap();
+                assert_eq!(data, buff);
+            }
+        }
+    }
+
+    #[test]
+    fn no_header() {
+        assert!(DirectIpMessage::create(vec![InformationElement::MOPayload(vec![])]).is_err());
+    }
+
+    /// Mutates the fixtures and checks that parsing and using the results never panics, like the
+    /// `parse` fuzz target does with arbitrary input.
+    #[test]
+    fn mutated_input_does_not_panic() {
+        use crate::{decoder, MessageRef, ParseOptions};
+
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        let fixtures = [
+            "data/0-mo.sbd",
+            "data/1-mo-location.sbd",
+            "data/data.sbd",
+            "data/iridium.ack",
+            "data/resp.sbd",
+        ];
+        for path in &fixtures {
+            let original = std::fs::read(path).unwrap();
+            for _ in 0..2000 {
+                let mut data = original.clone();
+                for _ in 0..random() % 4 + 1 {
+                    let index = random() as usize % data.len();
+                    data[index] = random() as u8;
+                }
+                data.truncate(random() as usize % (data.len() + 1));
+
+                for options in &[ParseOptions::lenient(), ParseOptions::strict()] {
+                    if let Ok(message) = DirectIpMessage::read_from_with(data.as_slice(), *options)
+                    {
+                        let _ = message.to_string();
+                        if let Some(message) = message.as_message() {
+                            let _ = message.imei().to_string();
+                        }
+                        let _ = message.write_to(&mut vec![]);
+                    }
+                    if let Ok(message) = MessageRef::parse_with(&data, *options) {
+                        let _ = message.imei().to_string();
+                        let _ = message.to_owned().write_to(&mut vec![]);
+                    }
+                }
+                let mut decoder = decoder::Decoder::new();
+                decoder.push(&data);
+                let _ = decoder.decode();
+            }
+        }
+    }
+}
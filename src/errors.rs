@@ -1,3 +1,4 @@
+use crate::Imei;
 use std::convert::From;
 use std::fmt::{Display, Formatter};
 
@@ -25,6 +26,27 @@ pub enum Error {
     /// The payload is too long.
     PayloadTooLong(usize),
 
+    /// The payload is larger than the gateway accepts.
+    PayloadTooLarge {
+        /// The payload length.
+        length: usize,
+        /// The largest payload the gateway accepts.
+        max: usize,
+    },
+
+    /// A field required to build a message was not set.
+    MissingField(&'static str),
+
+    /// The length declared by an information element does not match its contents.
+    IeLengthMismatch {
+        /// The information element identifier.
+        iei: u8,
+        /// The length declared in the information element.
+        declared: u16,
+        /// The length the information element requires.
+        expected: u16,
+    },
+
     /// No headers in a message
     NoHeader,
 
@@ -40,8 +62,126 @@ pub enum Error {
     /// Two locations in an message.
     TwoLocations,
 
+    /// Two priorities in an message.
+    TwoPriorities,
+
+    /// The mobile-terminated priority level is outside of 1 to 5.
+    InvalidPriority(u16),
+
+    /// The MT queue position is 0 or above `i16::MAX`, so it cannot be sent on the wire.
+    InvalidQueuePosition(u16),
+
+    /// The coordinates are not finite or outside of the valid latitude and longitude ranges.
+    InvalidCoordinates {
+        /// The latitude in decimal degrees.
+        latitude: f64,
+        /// The longitude in decimal degrees.
+        longitude: f64,
+    },
+
+    /// The location information uses a format code other than 0.
+    UnsupportedLocationFormat(u8),
+
+    /// The IMEI is not numeric, or fails the check digit when it is verified.
+    InvalidImei(Imei),
+
     /// The session status is unknown.
     UnknownSessionStatus(u8),
+
+    /// Reserved bits are set in the mobile-terminated disposition flags.
+    ReservedDispositionFlags(u16),
+
+    /// The message is not of the kind expected at this point of the exchange.
+    UnexpectedMessage,
+
+    /// An information element belongs to the other direction than the message header.
+    DirectionMismatch {
+        /// The information element identifier.
+        iei: u8,
+        /// Whether the message header is mobile-originated.
+        mobile_originated: bool,
+    },
+
+    /// Bytes follow the end of the message.
+    TrailingBytes(usize),
+
+    /// An error found while parsing, with where it was found.
+    ///
+    /// Every error about the contents of a frame is returned in this variant when reading or
+    /// parsing, including missing or duplicate information elements. Only errors of the reader
+    /// itself, other than it ending early, are returned as they are.
+    ///
+    /// It is displayed along with the error itself, so `source` does not return that error again;
+    /// use `Error::root` to get it.
+    Parse {
+        /// The offset of the field in the frame, or in the information element when reading a
+        /// single one.
+        offset: usize,
+        /// The identifier of the information element being parsed, if any.
+        iei: Option<u8>,
+        /// The name of the field being parsed.
+        field: &'static str,
+        /// The error itself.
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Adds where a parse error was found.
+    pub(crate) fn at(self, offset: usize, iei: Option<u8>, field: &'static str) -> Error {
+        Error::Parse {
+            offset,
+            iei,
+            field,
+            source: Box::new(self),
+        }
+    }
+
+    /// Returns the error without where it was found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sbd_lib::{Error, Message};
+    /// let mut data = std::fs::read("data/0-mo.sbd").unwrap();
+    /// data[0] = 2;
+    /// let err = Message::read_from(data.as_slice()).unwrap_err();
+    /// assert!(matches!(err.root(), Error::InvalidProtocolRevisionNumber(2)));
+    /// assert_eq!(Some(0), err.offset());
+    /// ```
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Parse { source, .. } => source.root(),
+            err => err,
+        }
+    }
+
+    /// Returns the offset of the field a parse error was found in.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Error::Parse { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Returns the identifier of the information element a parse error was found in.
+    pub fn iei(&self) -> Option<u8> {
+        match self {
+            Error::Parse { iei, .. } => *iei,
+            Error::IeLengthMismatch { iei, .. } | Error::DirectionMismatch { iei, .. } => {
+                Some(*iei)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the field a parse error was found in.
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            Error::Parse { field, .. } => Some(field),
+            _ => None,
+        }
+    }
 }
 
 /// Create-specific `Result`.
@@ -49,11 +189,121 @@ pub type Result<T> = ::std::result::Result<T, Error>;
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::InvalidProtocolRevisionNumber(n) => {
+                write!(f, "invalid protocol revision number {}", n)
+            }
+            Error::InvalidInformationElementIdentifier(iei) => {
+                write!(f, "invalid information element identifier {:#04x}", iei)
+            }
+            Error::NegativeTimestamp(timestamp) => write!(
+                f,
+                "the timestamp {} is before 1970, which cannot be encoded",
+                timestamp
+            ),
+            Error::InvalidTimeRange(timestamp) => {
+                write!(f, "the timestamp {} is out of range", timestamp)
+            }
+            Error::OverallMessageLength(length) => write!(
+                f,
+                "the overall message length {} exceeds {} bytes",
+                length,
+                u16::MAX
+            ),
+            Error::PayloadTooLong(length) => write!(
+                f,
+                "the payload length {} exceeds {} bytes",
+                length,
+                u16::MAX
+            ),
+            Error::PayloadTooLarge { length, max } => write!(
+                f,
+                "the payload length {} exceeds the {} bytes the gateway accepts",
+                length, max
+            ),
+            Error::MissingField(field) => write!(f, "the {} is required", field),
+            Error::IeLengthMismatch {
+                iei,
+                declared,
+                expected,
+            } => write!(
+                f,
+                "information element {:#04x} declares {} bytes but requires {}",
+                iei, declared, expected
+            ),
+            Error::NoHeader => write!(f, "the message has no header"),
+            Error::NoPayload => write!(f, "the message has no payload"),
+            Error::TwoHeaders => write!(f, "the message has two headers"),
+            Error::TwoPayloads => write!(f, "the message has two payloads"),
+            Error::TwoLocations => write!(f, "the message has two locations"),
+            Error::TwoPriorities => write!(f, "the message has two priorities"),
+            Error::InvalidPriority(level) => {
+                write!(f, "invalid priority level {}, expected 1 to 5", level)
+            }
+            Error::InvalidQueuePosition(position) => write!(
+                f,
+                "invalid MT queue position {}, expected 1 to {}",
+                position,
+                i16::MAX
+            ),
+            Error::InvalidCoordinates {
+                latitude,
+                longitude,
+            } => write!(f, "invalid coordinates {}, {}", latitude, longitude),
+            Error::UnsupportedLocationFormat(code) => {
+                write!(f, "unsupported location format code {}", code)
+            }
+            Error::InvalidImei(imei) => {
+                write!(f, "invalid IMEI {:?}", String::from_utf8_lossy(imei))
+            }
+            Error::UnknownSessionStatus(n) => write!(f, "unknown session status {}", n),
+            Error::ReservedDispositionFlags(bits) => {
+                write!(f, "reserved disposition flags are set in {:#06x}", bits)
+            }
+            Error::UnexpectedMessage => write!(f, "unexpected kind of message"),
+            Error::DirectionMismatch {
+                iei,
+                mobile_originated,
+            } => write!(
+                f,
+                "information element {:#04x} cannot be part of a {} message",
+                iei,
+                if *mobile_originated {
+                    "mobile-originated"
+                } else {
+                    "mobile-terminated"
+                }
+            ),
+            Error::TrailingBytes(n) => write!(f, "{} bytes follow the end of the message", n),
+            Error::Parse {
+                offset,
+                iei: Some(iei),
+                field,
+                source,
+            } => write!(
+                f,
+                "{} at offset {} ({} of information element {:#04x})",
+                source, offset, field, iei
+            ),
+            Error::Parse {
+                offset,
+                iei: None,
+                field,
+                source,
+            } => write!(f, "{} at offset {} ({})", source, offset, field),
+        }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl From<::std::io::Error> for Error {
     fn from(err: ::std::io::Error) -> Error {
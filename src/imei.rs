@@ -1,10 +1,29 @@
+use crate::{Error, Result};
 #[cfg(feature = "serde-derive")]
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 use std::fmt::{Display, Formatter};
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The International Mobile Equipment Identity of a device, as 15 ASCII digits.
+///
+/// An IMEI is made of the 8 digit Type Allocation Code, a 6 digit serial number and a Luhn check
+/// digit. Iridium gateways usually send `0` instead of the check digit, so it is only verified
+/// by `validate`.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::Imei;
+/// let imei: Imei = "490154203237518".parse().unwrap();
+/// assert_eq!(b"49015420", imei.tac());
+/// assert_eq!(b"323751", imei.serial_number());
+/// assert_eq!(Some(8), imei.check_digit());
+/// assert!(imei.validate().is_ok());
+/// assert_eq!(Some(490154203237518), imei.as_u64());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
 pub struct Imei(
     #[cfg_attr(
@@ -28,6 +47,84 @@ impl DerefMut for Imei {
     }
 }
 
+impl Imei {
+    /// Returns true if every character is an ASCII digit.
+    pub fn is_numeric(&self) -> bool {
+        self.0.iter().all(u8::is_ascii_digit)
+    }
+
+    /// Returns the Type Allocation Code, the first 8 digits.
+    pub fn tac(&self) -> &[u8] {
+        &self.0[..8]
+    }
+
+    /// Returns the serial number, the 6 digits following the Type Allocation Code.
+    pub fn serial_number(&self) -> &[u8] {
+        &self.0[8..14]
+    }
+
+    /// Computes the Luhn check digit of the first 14 digits.
+    ///
+    /// Returns `None` if the IMEI is not numeric.
+    pub fn check_digit(&self) -> Option<u8> {
+        if !self.is_numeric() {
+            return None;
+        }
+        let sum: u32 = self.0[..14]
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, digit)| {
+                let digit = u32::from(digit - b'0');
+                if i % 2 == 0 {
+                    let doubled = digit * 2;
+                    doubled / 10 + doubled % 10
+                } else {
+                    digit
+                }
+            })
+            .sum();
+        Some(((10 - sum % 10) % 10) as u8)
+    }
+
+    /// Checks that the IMEI is numeric and ends with its Luhn check digit.
+    pub fn validate(&self) -> Result<()> {
+        match self.check_digit() {
+            Some(digit) if self.0[14] == b'0' + digit => Ok(()),
+            _ => Err(Error::InvalidImei(*self)),
+        }
+    }
+
+    /// Returns the IMEI as a number, or `None` if it is not numeric.
+    pub fn as_u64(&self) -> Option<u64> {
+        if !self.is_numeric() {
+            return None;
+        }
+        Some(
+            self.0
+                .iter()
+                .fold(0, |value, digit| value * 10 + u64::from(digit - b'0')),
+        )
+    }
+}
+
+impl TryFrom<u64> for Imei {
+    type Error = ParseImeiError;
+
+    /// Creates an IMEI from a number of at most 15 digits, padded with leading zeros.
+    fn try_from(mut value: u64) -> std::result::Result<Self, Self::Error> {
+        if value >= 1_000_000_000_000_000 {
+            return Err(ParseImeiError);
+        }
+        let mut digits = [b'0'; 15];
+        for digit in digits.iter_mut().rev() {
+            *digit = b'0' + (value % 10) as u8;
+            value /= 10;
+        }
+        Ok(Imei(digits))
+    }
+}
+
 impl From<[u8; 15]> for Imei {
     fn from(val: [u8; 15]) -> Self {
         Self(val)
@@ -46,13 +143,25 @@ impl PartialEq<[u8; 15]> for Imei {
     }
 }
 
+impl PartialEq<str> for Imei {
+    fn eq(&self, other: &str) -> bool {
+        self.0.eq(other.as_bytes())
+    }
+}
+
+impl PartialEq<Imei> for str {
+    fn eq(&self, other: &Imei) -> bool {
+        other == self
+    }
+}
+
 #[derive(Debug)]
 pub struct ParseImeiError;
 
 impl FromStr for Imei {
     type Err = ParseImeiError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let mut value = [0; 15];
         let data = s.as_bytes();
 
@@ -103,3 +212,49 @@ where
         Err(de::Error::custom("wrong imei"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_digit() {
+        let imei: Imei = "490154203237518".parse().unwrap();
+        assert_eq!(Some(8), imei.check_digit());
+        assert!(imei.validate().is_ok());
+
+        let imei: Imei = "300234063904190".parse().unwrap();
+        assert_eq!(Some(9), imei.check_digit());
+        assert!(matches!(imei.validate(), Err(Error::InvalidImei(i)) if i == imei));
+
+        let imei = Imei::from([0; 15]);
+        assert_eq!(None, imei.check_digit());
+        assert!(imei.validate().is_err());
+    }
+
+    #[test]
+    fn parts() {
+        let imei: Imei = "300234063904190".parse().unwrap();
+        assert_eq!(b"30023406", imei.tac());
+        assert_eq!(b"390419", imei.serial_number());
+    }
+
+    #[test]
+    fn roundtrip_u64() {
+        for value in &[0, 42, 300234063904190, 999_999_999_999_999] {
+            let imei = Imei::try_from(*value).unwrap();
+            assert_eq!(Some(*value), imei.as_u64());
+        }
+        assert_eq!("000000000000042", Imei::try_from(42).unwrap().to_string());
+        assert!(Imei::try_from(1_000_000_000_000_000).is_err());
+        assert_eq!(None, Imei::from([0; 15]).as_u64());
+    }
+
+    #[test]
+    fn ordering() {
+        let low = Imei::try_from(300234063904190).unwrap();
+        let high = Imei::try_from(300434060009290).unwrap();
+        assert!(low < high);
+        assert_eq!(low.as_u64() < high.as_u64(), low < high);
+    }
+}
@@ -1,16 +1,18 @@
+use crate::message_ref::{frame_length, value_error, FrameParser, InformationElementRef};
 use crate::mo::LocationInformation;
-use crate::{mo, mt, Result};
-use byteorder;
+use crate::{mo, mt, Imei, Message, ParseOptions, Result};
 #[cfg(feature = "serde-derive")]
 use serde::{Deserialize, Serialize};
-use std::{fmt, io::Cursor, io::Read, io::Write};
-
-const PROTOCOL_REVISION_NUMBER: u8 = 1;
+use std::{fmt, io::Read, io::Write};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
+// Headers always include their IEI and length, so they are never empty.
+#[allow(clippy::len_without_is_empty)]
 pub trait SbdHeader: fmt::Debug {
     //fn read_from(read: &Read) -> Result<Box<Self>>;
     fn write_to(&self, write: &mut dyn Write) -> Result<()>;
-    fn imei(&self) -> &str;
+    fn imei(&self) -> &Imei;
     fn len(&self) -> usize;
     fn as_mo(&self) -> Option<&mo::Header> {
         None
@@ -63,7 +65,7 @@ impl SbdHeader for Header {
         Ok(())
     }
 
-    fn imei(&self) -> &str {
+    fn imei(&self) -> &Imei {
         match self {
             Header::MOHeader(header) => header.imei(),
             Header::MTHeader(header) => header.imei(),
@@ -106,7 +108,7 @@ impl From<mt::Header> for Header {
 }
 
 /// A mobile-originated or mobile-terminated status.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
 pub enum Status {
     /// Information element holding the mobile-originated status.
@@ -124,6 +126,7 @@ impl Status {
         Ok(())
     }
 
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         match self {
             Status::MOStatus(status) => status.len(),
@@ -160,75 +163,188 @@ pub enum InformationElement {
     Status(Status),
     /// The mobile originated location information.
     LocationInformation(LocationInformation),
+    /// The mobile terminated message priority.
+    Priority(mt::MtPriority),
+    /// An information element this library does not know, kept as raw bytes.
+    Unknown {
+        /// The information element identifier.
+        iei: u8,
+        /// The information element contents, without the IEI and length.
+        data: Vec<u8>,
+    },
+}
+
+/// The information elements of a frame, as they were read.
+pub(crate) struct ParsedFrame {
+    pub(crate) information_elements: Vec<InformationElement>,
+    /// The offset of each information element in the frame.
+    pub(crate) offsets: Vec<usize>,
+    /// Bytes lenient parsing skipped past the length an information element requires, by
+    /// position among the information elements.
+    pub(crate) skipped: Vec<(usize, Vec<u8>)>,
 }
 
 impl InformationElement {
     /// Reads this information element from a `Read`.
-    pub fn read_single<R: Read>(mut read: R) -> Result<Self> {
+    pub fn read_single<R: Read>(read: R) -> Result<Self> {
+        Self::read_single_with(read, ParseOptions::default())
+    }
+
+    /// Reads this information element from a `Read` with the given options.
+    pub fn read_single_with<R: Read>(mut read: R, options: ParseOptions) -> Result<Self> {
         use crate::Error;
         use byteorder::{BigEndian, ReadBytesExt};
 
-        let iei = read.read_u8().map_err(Error::Io)?;
-        let length = read.read_u16::<BigEndian>().map_err(Error::Io)?;
-        match iei {
-            0x1 => Ok(mo::Header::read_from(&mut read)?.into()),
-            0x41 => Ok(mt::Header::read_from(&mut read)?.into()),
-            0x2 | 0x42 => {
-                let mut payload = vec![0; length as usize];
-                read.read_exact(&mut payload).map_err(Error::Io)?;
-                Ok(if iei == 0x2 {
-                    InformationElement::MOPayload(payload)
-                } else {
-                    InformationElement::MTPayload(payload)
-                })
-            }
-            0x3 => {
-                let mut location = vec![0; length as usize];
-                read.read_exact(&mut location).map_err(Error::Io)?;
-                let mut cur = Cursor::new(&location);
-                let flags = cur.read_u8()?;
-                let latitude = (cur.read_u8()?, cur.read_u16::<BigEndian>()?);
-                let longitude = (cur.read_u8()?, cur.read_u16::<BigEndian>()?);
-
-                let radius = if length == 11 {
-                    Some(cur.read_u32::<BigEndian>()?)
-                } else {
-                    None
-                };
+        let iei = read
+            .read_u8()
+            .map_err(|err| Error::Io(err).at(0, None, "iei"))?;
+        let length = read
+            .read_u16::<BigEndian>()
+            .map_err(|err| Error::Io(err).at(1, Some(iei), "length"))?;
+        let mut value = vec![0; length as usize];
+        read.read_exact(&mut value)
+            .map_err(|err| Error::Io(err).at(3, Some(iei), "value"))?;
+        Self::from_value(iei, value, options).map_err(|err| value_error(err, iei, 0))
+    }
+
+    /// Parses an information element from its identifier and contents, reusing the contents
+    /// allocation for payloads.
+    fn from_value(iei: u8, value: Vec<u8>, options: ParseOptions) -> Result<Self> {
+        Ok(
+            match InformationElementRef::from_value(iei, &value, options)? {
+                InformationElementRef::MOPayload(_) => InformationElement::MOPayload(value),
+                InformationElementRef::MTPayload(_) => InformationElement::MTPayload(value),
+                InformationElementRef::Unknown { iei, .. } => {
+                    InformationElement::Unknown { iei, data: value }
+                }
+                information_element => information_element.to_owned(),
+            },
+        )
+    }
 
-                let loc = LocationInformation::new(flags, latitude, longitude, radius);
+    pub fn parse<R: Read>(read: R) -> Result<Vec<Self>> {
+        Self::parse_with(read, ParseOptions::default())
+    }
 
-                Ok(InformationElement::LocationInformation(loc))
-            }
-            0x44 => Ok(mt::ConfirmationStatus::read_from(&mut read)?.into()),
-            0x5 => Ok(mo::ConfirmationStatus::read_from(&mut read)?.into()),
+    /// Reads all information elements of a message from a `Read` with the given options.
+    pub fn parse_with<R: Read>(read: R, options: ParseOptions) -> Result<Vec<Self>> {
+        Ok(Self::parse_frame(&Self::read_frame(read)?, options)?.information_elements)
+    }
 
-            _ => Err(Error::InvalidInformationElementIdentifier(iei)),
+    /// Reads a frame up to its overall message length, or as far as it goes.
+    pub(crate) fn read_frame<R: Read>(mut read: R) -> Result<Vec<u8>> {
+        let mut frame = Vec::new();
+        read.by_ref()
+            .take(Message::HEADER_SIZE as u64)
+            .read_to_end(&mut frame)?;
+        if let Some(length) = frame_length(&frame)? {
+            read.take((length - frame.len()) as u64)
+                .read_to_end(&mut frame)?;
         }
+        Ok(frame)
     }
 
-    pub fn parse<R: Read>(mut read: R) -> Result<Vec<Self>> {
-        use crate::Error;
-        use byteorder::{BigEndian, ReadBytesExt};
+    /// Reads this information element from an `AsyncRead`.
+    #[cfg(feature = "tokio")]
+    pub async fn read_single_async<R: AsyncRead + Unpin>(read: R) -> Result<Self> {
+        Self::read_single_async_with(read, ParseOptions::default()).await
+    }
 
-        let protocol_revision_number = read.read_u8()?;
-        if protocol_revision_number != PROTOCOL_REVISION_NUMBER {
-            return Err(Error::InvalidProtocolRevisionNumber(
-                protocol_revision_number,
-            ));
+    /// Reads this information element from an `AsyncRead` with the given options.
+    #[cfg(feature = "tokio")]
+    pub async fn read_single_async_with<R: AsyncRead + Unpin>(
+        mut read: R,
+        options: ParseOptions,
+    ) -> Result<Self> {
+        use crate::Error;
+        use byteorder::{BigEndian, ByteOrder};
+
+        let mut header = Vec::new();
+        (&mut read).take(3).read_to_end(&mut header).await?;
+        if header.len() < 3 {
+            let (offset, iei, field) = match header.first() {
+                Some(&iei) => (1, Some(iei), "length"),
+                None => (0, None, "iei"),
+            };
+            return Err(crate::message_ref::unexpected_eof().at(offset, iei, field));
         }
-        let overall_message_length = read.read_u16::<BigEndian>()?;
-        let mut message = vec![0; overall_message_length as usize];
-        read.read_exact(&mut message)?;
-
-        let mut cursor = Cursor::new(message);
-        let mut information_elements = Vec::new();
-        while cursor.position() < u64::from(overall_message_length) {
-            information_elements.push(InformationElement::read_single(&mut cursor)?);
+        let iei = header[0];
+        let mut value = vec![0; BigEndian::read_u16(&header[1..]) as usize];
+        read.read_exact(&mut value)
+            .await
+            .map_err(|err| Error::Io(err).at(3, Some(iei), "value"))?;
+        Self::from_value(iei, value, options).map_err(|err| value_error(err, iei, 0))
+    }
+
+    /// Reads all information elements of a message from an `AsyncRead`.
+    #[cfg(feature = "tokio")]
+    pub async fn parse_async<R: AsyncRead + Unpin>(read: R) -> Result<Vec<Self>> {
+        Self::parse_async_with(read, ParseOptions::default()).await
+    }
+
+    /// Reads all information elements of a message from an `AsyncRead` with the given options.
+    #[cfg(feature = "tokio")]
+    pub async fn parse_async_with<R: AsyncRead + Unpin>(
+        read: R,
+        options: ParseOptions,
+    ) -> Result<Vec<Self>> {
+        Ok(Self::parse_frame(&Self::read_frame_async(read).await?, options)?.information_elements)
+    }
+
+    /// Reads a frame from an `AsyncRead` up to its overall message length, or as far as it goes.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn read_frame_async<R: AsyncRead + Unpin>(mut read: R) -> Result<Vec<u8>> {
+        let mut frame = Vec::new();
+        (&mut read)
+            .take(Message::HEADER_SIZE as u64)
+            .read_to_end(&mut frame)
+            .await?;
+        if let Some(length) = frame_length(&frame)? {
+            read.take((length - frame.len()) as u64)
+                .read_to_end(&mut frame)
+                .await?;
+        }
+        Ok(frame)
+    }
+
+    /// Parses the information elements of a frame, as far as it was received.
+    pub(crate) fn parse_frame(frame: &[u8], options: ParseOptions) -> Result<ParsedFrame> {
+        let mut offsets = Vec::new();
+        let mut skipped = Vec::new();
+        let information_elements = FrameParser::new(frame, options)?
+            .enumerate()
+            .map(|(position, information_element)| {
+                let (information_element, offset, skipped_bytes) = information_element?;
+                offsets.push(offset);
+                if !skipped_bytes.is_empty() {
+                    skipped.push((position, skipped_bytes.to_vec()));
+                }
+                Ok(information_element.to_owned())
+            })
+            .collect::<Result<_>>()?;
+        Ok(ParsedFrame {
+            information_elements,
+            offsets,
+            skipped,
+        })
+    }
+
+    /// Returns the information element identifier.
+    pub fn iei(&self) -> u8 {
+        match self {
+            InformationElement::Header(Header::MOHeader(_)) => 0x01,
+            InformationElement::Header(Header::MTHeader(_)) => 0x41,
+            InformationElement::MOPayload(_) => 0x02,
+            InformationElement::MTPayload(_) => 0x42,
+            InformationElement::Status(Status::MOStatus(_)) => 0x05,
+            InformationElement::Status(Status::MTStatus(_)) => 0x44,
+            InformationElement::LocationInformation(_) => 0x03,
+            InformationElement::Priority(_) => 0x46,
+            InformationElement::Unknown { iei, .. } => *iei,
         }
-        Ok(information_elements)
     }
 
+    #[allow(clippy::len_without_is_empty)]
     /// Returns the length of this information element, including the information element header.
     pub fn len(&self) -> usize {
         match self {
@@ -238,6 +354,8 @@ impl InformationElement {
                 payload.len() + 3
             }
             InformationElement::LocationInformation(location) => location.len(),
+            InformationElement::Priority(priority) => priority.len(),
+            InformationElement::Unknown { data, .. } => data.len() + 3,
         }
     }
 
@@ -286,9 +404,31 @@ impl InformationElement {
             InformationElement::LocationInformation(location) => {
                 location.write_to(&mut write)?;
             }
+            InformationElement::Priority(priority) => {
+                priority.write_to(&mut write)?;
+            }
+            InformationElement::Unknown { iei, data } => {
+                write.write_u8(*iei)?;
+                let len = data.len();
+                if len > u16::MAX as usize {
+                    return Err(Error::PayloadTooLong(len));
+                } else {
+                    write.write_u16::<BigEndian>(len as u16)?;
+                }
+                write.write_all(data)?;
+            }
         }
         Ok(())
     }
+
+    /// Writes this information element to an `AsyncWrite`.
+    #[cfg(feature = "tokio")]
+    pub async fn write_to_async<W: AsyncWrite + Unpin>(&self, mut write: W) -> Result<()> {
+        let mut buff = Vec::with_capacity(self.len());
+        self.write_to(&mut buff)?;
+        write.write_all(&buff).await?;
+        Ok(())
+    }
 }
 
 impl From<mo::ConfirmationStatus> for InformationElement {
@@ -321,6 +461,12 @@ impl From<mo::LocationInformation> for InformationElement {
     }
 }
 
+impl From<mt::MtPriority> for InformationElement {
+    fn from(priority: mt::MtPriority) -> Self {
+        InformationElement::Priority(priority)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,7 +524,7 @@ mod tests {
     fn header_len() {
         let header = mo::Header {
             auto_id: 1,
-            imei: [0; 15].into(),
+            imei: (*b"300234063904190").into(),
             session_status: mo::SessionStatus::Ok,
             momsn: 1,
             mtmsn: 1,
@@ -426,7 +572,7 @@ mod tests {
     fn roundtrip_header() {
         let header = mo::Header {
             auto_id: 1,
-            imei: [0; 15].into(),
+            imei: (*b"300234063904190").into(),
             session_status: mo::SessionStatus::Ok,
             momsn: 1,
             mtmsn: 1,
@@ -443,11 +589,162 @@ mod tests {
         assert_eq!(ie, InformationElement::read_single(&mut cursor).unwrap());
     }
 
+    #[test]
+    fn non_numeric_imei() {
+        use crate::{Error, ParseOptions};
+
+        let mut data = vec![];
+        let header = mt::Header {
+            message_id: 1,
+            imei: (*b"30043406000929\xff").into(),
+            flags: mt::DispositionFlags::empty(),
+        };
+        InformationElement::from(header)
+            .write_to(&mut data)
+            .unwrap();
+        assert!(matches!(
+            InformationElement::read_single(data.as_slice()).as_ref().map_err(Error::root),
+            Err(Error::InvalidImei(imei)) if *imei == header.imei
+        ));
+        assert_eq!("Incorrect imei", header.imei.to_string());
+
+        let file = File::open("data/0-mo.sbd").unwrap();
+        let options = ParseOptions::strict().verify_check_digit(true);
+        assert!(matches!(
+            InformationElement::parse_with(file, options).as_ref().map_err(Error::root),
+            Err(Error::InvalidImei(imei)) if *imei == *b"300234063904190"
+        ));
+    }
+
+    #[test]
+    fn roundtrip_mt_header_reserved_flags() {
+        let header = mt::Header {
+            message_id: 1,
+            imei: (*b"300434060009290").into(),
+            flags: mt::DispositionFlags::from_bits_retain(0x8002),
+        };
+        let ie = InformationElement::Header(header.into());
+        let mut cursor = Cursor::new(Vec::new());
+        ie.write_to(&mut cursor).unwrap();
+        cursor.set_position(0);
+        assert_eq!(ie, InformationElement::read_single(&mut cursor).unwrap());
+
+        cursor.set_position(0);
+        let err = InformationElement::read_single_with(&mut cursor, crate::ParseOptions::strict())
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            crate::Error::ReservedDispositionFlags(0x8002)
+        ));
+        assert_eq!((Some(22), Some("flags")), (err.offset(), err.field()));
+    }
+
+    #[test]
+    fn roundtrip_priority() {
+        let ie = InformationElement::from(mt::MtPriority::new(2).unwrap());
+        let mut buff = vec![];
+        ie.write_to(&mut buff).unwrap();
+        assert_eq!(ie.len(), buff.len());
+        assert_eq!(
+            ie,
+            InformationElement::read_single(buff.as_slice()).unwrap()
+        );
+        assert_eq!(
+            InformationElement::Unknown {
+                iei: 0x46,
+                data: vec![0, 6]
+            },
+            InformationElement::read_single(&[0x46, 0, 2, 0, 6][..]).unwrap()
+        );
+        assert!(InformationElement::read_single_with(
+            &[0x46, 0, 2, 0, 6][..],
+            crate::ParseOptions::strict()
+        )
+        .is_err());
+        assert!(InformationElement::read_single_with(
+            &[0x46, 0, 3, 0, 1, 0][..],
+            crate::ParseOptions::strict()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn iei() {
+        for path in &[
+            "data/1-mo-location.sbd",
+            "data/resp.sbd",
+            "data/iridium.ack",
+        ] {
+            for ie in InformationElement::parse(File::open(path).unwrap()).unwrap() {
+                let mut buff = vec![];
+                ie.write_to(&mut buff).unwrap();
+                assert_eq!(buff[0], ie.iei(), "{:?}", ie);
+            }
+        }
+        assert_eq!(0x46, InformationElement::from(mt::MtPriority::LOWEST).iei());
+    }
+
+    #[test]
+    fn declared_length() {
+        use crate::{Error, ParseOptions};
+
+        let data = std::fs::read("data/resp.sbd").unwrap();
+        let status = InformationElement::read_single(&data[3..]).unwrap();
+        let mut longer = vec![0x44, 0, 27];
+        longer.extend_from_slice(&data[6..]);
+        longer.extend_from_slice(&[0xff, 0xff, 0x05, 0, 1, 1]);
+
+        let mut read = longer.as_slice();
+        assert_eq!(status, InformationElement::read_single(&mut read).unwrap());
+        assert_eq!(
+            InformationElement::from(mo::ConfirmationStatus::new(true)),
+            InformationElement::read_single(&mut read).unwrap()
+        );
+        assert!(matches!(
+            InformationElement::read_single_with(longer.as_slice(), ParseOptions::strict())
+                .as_ref()
+                .map_err(Error::root),
+            Err(Error::IeLengthMismatch {
+                iei: 0x44,
+                declared: 27,
+                expected: 25
+            })
+        ));
+
+        let mut shorter = data[3..30].to_vec();
+        shorter[2] = 24;
+        assert!(matches!(
+            InformationElement::read_single(shorter.as_slice())
+                .as_ref()
+                .map_err(Error::root),
+            Err(Error::IeLengthMismatch {
+                iei: 0x44,
+                declared: 24,
+                expected: 25
+            })
+        ));
+
+        let mut header = std::fs::read("data/0-mo.sbd").unwrap()[3..34].to_vec();
+        header[2] = 29;
+        header.push(0);
+        assert!(InformationElement::read_single(header.as_slice()).is_ok());
+        assert!(matches!(
+            InformationElement::read_single_with(header.as_slice(), ParseOptions::strict())
+                .as_ref()
+                .map_err(Error::root),
+            Err(Error::IeLengthMismatch {
+                iei: 0x01,
+                declared: 29,
+                expected: 28
+            })
+        ));
+    }
+
     #[test]
     fn header_time_of_session_too_old() {
         let header = mo::Header {
             auto_id: 1,
-            imei: [0; 15].into(),
+            imei: (*b"300234063904190").into(),
             session_status: mo::SessionStatus::Ok,
             momsn: 1,
             mtmsn: 1,
@@ -474,13 +771,41 @@ mod tests {
 
     #[test]
     fn payload_too_long() {
-        use std::u16;
         let payload = vec![0; u16::MAX as usize + 1];
         assert!(InformationElement::MOPayload(payload)
             .write_to(&mut Cursor::new(Vec::new()))
             .is_err());
     }
 
+    #[test]
+    fn roundtrip_unknown() {
+        let ie = InformationElement::Unknown {
+            iei: 0x7f,
+            data: vec![1, 2, 3],
+        };
+        assert_eq!(6, ie.len());
+        let mut cursor = Cursor::new(Vec::new());
+        ie.write_to(&mut cursor).unwrap();
+        assert_eq!(vec![0x7f, 0, 3, 1, 2, 3], *cursor.get_ref());
+        cursor.set_position(0);
+        assert_eq!(ie, InformationElement::read_single(cursor).unwrap());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_roundtrip_payload() {
+        let ie = InformationElement::MOPayload(vec![1, 2, 3]);
+        let mut buff = Vec::new();
+        ie.write_to_async(&mut buff).await.unwrap();
+        assert_eq!(vec![2, 0, 3, 1, 2, 3], buff);
+        assert_eq!(
+            ie,
+            InformationElement::read_single_async(buff.as_slice())
+                .await
+                .unwrap()
+        );
+    }
+
     #[test]
     fn roundtrip_location_information() {
         let ie = InformationElement::LocationInformation(LocationInformation::new(
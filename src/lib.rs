@@ -1,13 +1,28 @@
+pub mod client;
+#[cfg(feature = "tokio")]
+pub mod codec;
+pub mod decoder;
+pub mod direct_ip_message;
 mod errors;
 mod imei;
 pub mod information_element;
+pub mod message_ref;
 pub mod mo;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock_gateway;
 pub mod mt;
+mod parse_options;
 pub mod sbd_message;
+pub mod server;
+pub mod validation;
 
 pub use imei::Imei;
 
+pub use direct_ip_message::DirectIpMessage;
 pub use errors::{Error, Result};
+pub use message_ref::MessageRef;
+pub use parse_options::ParseOptions;
 pub use sbd_message::Message;
+pub use validation::{validate, ValidationReport};
 
 pub use information_element::{Header, InformationElement, SbdHeader};
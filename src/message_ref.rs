@@ -0,0 +1,782 @@
+use crate::{
+    information_element::{Header, InformationElement, SbdHeader, Status},
+    mo::{self, LocationFlags, LocationInformation},
+    mt,
+    sbd_message::IeSlot,
+    Error, Imei, Message, ParseOptions, Result,
+};
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+use std::io;
+
+const PROTOCOL_REVISION_NUMBER: u8 = 1;
+
+pub(crate) fn unexpected_eof() -> Error {
+    Error::Io(io::ErrorKind::UnexpectedEof.into())
+}
+
+/// Returns the length of the frame starting at `data`, protocol header included.
+///
+/// Returns `None` until the protocol header is complete.
+pub(crate) fn frame_length(data: &[u8]) -> Result<Option<usize>> {
+    if data.len() < Message::HEADER_SIZE {
+        return Ok(None);
+    }
+    if data[0] != PROTOCOL_REVISION_NUMBER {
+        return Err(Error::InvalidProtocolRevisionNumber(data[0]).at(
+            0,
+            None,
+            "protocol_revision_number",
+        ));
+    }
+    let overall_message_length = BigEndian::read_u16(&data[1..3]) as usize;
+    Ok(Some(Message::HEADER_SIZE + overall_message_length))
+}
+
+/// Parses the information elements of an SBD frame one by one, protocol header included.
+///
+/// A frame cut short is parsed as far as it goes, so the error points at the first incomplete
+/// information element. Bytes past the overall message length are ignored, just like
+/// `Message::read_from` leaves them in the reader.
+pub(crate) struct FrameParser<'a> {
+    body: &'a [u8],
+    offset: usize,
+    end: usize,
+    options: ParseOptions,
+    done: bool,
+}
+
+impl<'a> FrameParser<'a> {
+    pub(crate) fn new(data: &'a [u8], options: ParseOptions) -> Result<Self> {
+        let end = match frame_length(data)? {
+            Some(end) => end,
+            None if data.is_empty() => {
+                return Err(unexpected_eof().at(0, None, "protocol_revision_number"))
+            }
+            None => return Err(unexpected_eof().at(1, None, "overall_message_length")),
+        };
+        Ok(FrameParser {
+            body: &data[Message::HEADER_SIZE..end.min(data.len())],
+            offset: Message::HEADER_SIZE,
+            end,
+            options,
+            done: false,
+        })
+    }
+}
+
+impl<'a> Iterator for FrameParser<'a> {
+    /// An information element, its offset in the frame and the bytes lenient parsing skipped past
+    /// the length it requires.
+    type Item = Result<(InformationElementRef<'a>, usize, &'a [u8])>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.body.is_empty() {
+            self.done = true;
+            if self.offset < self.end {
+                return Some(Err(unexpected_eof().at(self.offset, None, "iei")));
+            }
+            return None;
+        }
+        let offset = self.offset;
+        match InformationElementRef::parse_single_at(self.body, self.options, offset) {
+            Ok((information_element, rest)) => {
+                let skipped = &self.body[information_element.len()..self.body.len() - rest.len()];
+                self.offset += self.body.len() - rest.len();
+                self.body = rest;
+                Some(Ok((information_element, offset, skipped)))
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Returns whether an information element belongs to mobile-originated messages, or `None` for
+/// identifiers this library does not know.
+pub(crate) fn is_mobile_originated(iei: u8) -> Option<bool> {
+    match iei {
+        0x01 | 0x02 | 0x03 | 0x05 => Some(true),
+        0x41 | 0x42 | 0x44 | 0x46 => Some(false),
+        _ => None,
+    }
+}
+
+/// Checks every information element belongs to the same direction as the header.
+///
+/// Returns the position of the first one that does not along with the error.
+pub(crate) fn check_directions<I: IntoIterator<Item = u8>>(
+    header: &Header,
+    ieis: I,
+) -> std::result::Result<(), (usize, Error)> {
+    let mobile_originated = matches!(header, Header::MOHeader(_));
+    match ieis
+        .into_iter()
+        .enumerate()
+        .find(|&(_, iei)| is_mobile_originated(iei) == Some(!mobile_originated))
+    {
+        Some((position, iei)) => Err((
+            position,
+            Error::DirectionMismatch {
+                iei,
+                mobile_originated,
+            },
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Adds where an error about a whole information element was found, e.g. a second header.
+pub(crate) fn misplaced_error(err: Error, iei: u8, offset: usize) -> Error {
+    err.at(offset, Some(iei), "iei")
+}
+
+/// Adds where an error about the whole message was found, e.g. a missing header: its information
+/// elements, right after the protocol header.
+pub(crate) fn message_error(err: Error) -> Error {
+    err.at(Message::HEADER_SIZE, None, "information_elements")
+}
+
+/// Adds where an error parsing the value of an information element was found.
+///
+/// `offset` is where the information element starts.
+pub(crate) fn value_error(err: Error, iei: u8, offset: usize) -> Error {
+    let (field, field_offset) = match err {
+        Error::IeLengthMismatch { .. } => ("length", 1),
+        Error::InvalidImei(_) => ("imei", 7),
+        Error::UnknownSessionStatus(_) => ("session_status", 22),
+        Error::UnsupportedLocationFormat(_) => ("flags", 3),
+        Error::InvalidPriority(_) => ("priority", 3),
+        Error::ReservedDispositionFlags(_) => ("flags", 22),
+        _ => ("value", 3),
+    };
+    err.at(offset + field_offset, Some(iei), field)
+}
+
+/// A information element borrowed from a byte slice.
+///
+/// Payloads and unknown information elements point into the parsed bytes, everything else is
+/// small enough to be copied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InformationElementRef<'a> {
+    /// Information element holding the header MO or MT.
+    Header(Header),
+    /// The mobile originated payload.
+    MOPayload(&'a [u8]),
+    /// The mobile terminated payload.
+    MTPayload(&'a [u8]),
+    /// Message Delivery Confirmation
+    Status(Status),
+    /// The mobile originated location information.
+    LocationInformation(LocationInformation),
+    /// The mobile terminated message priority.
+    Priority(mt::MtPriority),
+    /// An information element this library does not know.
+    Unknown {
+        /// The information element identifier.
+        iei: u8,
+        /// The information element contents, without the IEI and length.
+        data: &'a [u8],
+    },
+}
+
+impl<'a> InformationElementRef<'a> {
+    /// Parses the information element at the start of `data`.
+    ///
+    /// Returns the information element and the bytes following it.
+    pub fn parse_single(data: &'a [u8]) -> Result<(Self, &'a [u8])> {
+        Self::parse_single_with(data, ParseOptions::default())
+    }
+
+    /// Parses the information element at the start of `data` with the given options.
+    pub fn parse_single_with(data: &'a [u8], options: ParseOptions) -> Result<(Self, &'a [u8])> {
+        Self::parse_single_at(data, options, 0)
+    }
+
+    /// Parses the information element at the start of `data`, found at `offset` in the frame.
+    fn parse_single_at(
+        data: &'a [u8],
+        options: ParseOptions,
+        offset: usize,
+    ) -> Result<(Self, &'a [u8])> {
+        let iei = *data
+            .first()
+            .ok_or_else(|| unexpected_eof().at(offset, None, "iei"))?;
+        if data.len() < 3 {
+            return Err(unexpected_eof().at(offset + 1, Some(iei), "length"));
+        }
+        let length = BigEndian::read_u16(&data[1..3]) as usize;
+        let rest = &data[3..];
+        if rest.len() < length {
+            return Err(unexpected_eof().at(offset + 3, Some(iei), "value"));
+        }
+        let (value, rest) = rest.split_at(length);
+        let information_element =
+            Self::from_value(iei, value, options).map_err(|err| value_error(err, iei, offset))?;
+        Ok((information_element, rest))
+    }
+
+    /// Parses all information elements of a complete SBD frame, protocol header included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sbd_lib::message_ref::InformationElementRef;
+    /// let data = std::fs::read("data/0-mo.sbd").unwrap();
+    /// let information_elements = InformationElementRef::parse(&data).unwrap();
+    /// assert_eq!(2, information_elements.len());
+    /// ```
+    pub fn parse(data: &'a [u8]) -> Result<Vec<Self>> {
+        Self::parse_with(data, ParseOptions::default())
+    }
+
+    /// Parses all information elements of a complete SBD frame with the given options.
+    pub fn parse_with(data: &'a [u8], options: ParseOptions) -> Result<Vec<Self>> {
+        FrameParser::new(data, options)?
+            .map(|information_element| information_element.map(|(ie, _, _)| ie))
+            .collect()
+    }
+
+    /// Parses the value of an information element, given its identifier.
+    ///
+    /// Values shorter than their information element requires are always rejected. Longer ones are
+    /// rejected by strict parsing, and otherwise read up to the required length, except for
+    /// locations of 8 to 10 bytes which are always rejected. Priority levels outside of 1 to 5 are
+    /// rejected by strict parsing, and otherwise kept as an unknown information element.
+    pub(crate) fn from_value(iei: u8, mut value: &'a [u8], options: ParseOptions) -> Result<Self> {
+        let data = value;
+        let length = value.len();
+        let check_length = |expected: usize| {
+            if length < expected || (options.is_strict() && length != expected) {
+                Err(Error::IeLengthMismatch {
+                    iei,
+                    declared: length as u16,
+                    expected: expected as u16,
+                })
+            } else {
+                Ok(())
+            }
+        };
+        match iei {
+            0x1 => {
+                check_length(28)?;
+                Ok(InformationElementRef::Header(
+                    mo::Header::read_from_with(&mut value, options)?.into(),
+                ))
+            }
+            0x41 => {
+                check_length(21)?;
+                Ok(InformationElementRef::Header(
+                    mt::Header::read_from_with(&mut value, options)?.into(),
+                ))
+            }
+            0x2 => Ok(InformationElementRef::MOPayload(value)),
+            0x42 => Ok(InformationElementRef::MTPayload(value)),
+            0x3 => {
+                if (8..11).contains(&length) {
+                    // A partial radius would be dropped, so this is rejected even when lenient.
+                    return Err(Error::IeLengthMismatch {
+                        iei,
+                        declared: length as u16,
+                        expected: 11,
+                    });
+                }
+                check_length(if length < 11 { 7 } else { 11 })?;
+                let flags = value.read_u8()?;
+                let format_code = LocationFlags::from_bits(flags).format_code();
+                if options.is_strict() && format_code != 0 {
+                    return Err(Error::UnsupportedLocationFormat(format_code));
+                }
+                let latitude = (value.read_u8()?, value.read_u16::<BigEndian>()?);
+                let longitude = (value.read_u8()?, value.read_u16::<BigEndian>()?);
+
+                let radius = if length >= 11 {
+                    Some(value.read_u32::<BigEndian>()?)
+                } else {
+                    None
+                };
+
+                Ok(InformationElementRef::LocationInformation(
+                    LocationInformation::new(flags, latitude, longitude, radius),
+                ))
+            }
+            0x44 => {
+                check_length(25)?;
+                Ok(InformationElementRef::Status(
+                    mt::ConfirmationStatus::read_from(&mut value)?.into(),
+                ))
+            }
+            0x5 => {
+                check_length(1)?;
+                Ok(InformationElementRef::Status(
+                    mo::ConfirmationStatus::read_from(&mut value)?.into(),
+                ))
+            }
+            0x46 => {
+                check_length(2)?;
+                match mt::MtPriority::read_from(&mut value) {
+                    Ok(priority) => Ok(InformationElementRef::Priority(priority)),
+                    Err(Error::InvalidPriority(_)) if !options.is_strict() => {
+                        Ok(InformationElementRef::Unknown { iei, data })
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            _ => Ok(InformationElementRef::Unknown { iei, data: value }),
+        }
+    }
+
+    /// Returns the information element identifier.
+    pub fn iei(&self) -> u8 {
+        match self {
+            InformationElementRef::Header(Header::MOHeader(_)) => 0x01,
+            InformationElementRef::Header(Header::MTHeader(_)) => 0x41,
+            InformationElementRef::MOPayload(_) => 0x02,
+            InformationElementRef::MTPayload(_) => 0x42,
+            InformationElementRef::Status(Status::MOStatus(_)) => 0x05,
+            InformationElementRef::Status(Status::MTStatus(_)) => 0x44,
+            InformationElementRef::LocationInformation(_) => 0x03,
+            InformationElementRef::Priority(_) => 0x46,
+            InformationElementRef::Unknown { iei, .. } => *iei,
+        }
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    /// Returns the length of this information element, including the information element header.
+    pub fn len(&self) -> usize {
+        match self {
+            InformationElementRef::Header(header) => header.len(),
+            InformationElementRef::Status(status) => status.len(),
+            InformationElementRef::MOPayload(payload)
+            | InformationElementRef::MTPayload(payload) => payload.len() + 3,
+            InformationElementRef::LocationInformation(location) => location.len(),
+            InformationElementRef::Priority(priority) => priority.len(),
+            InformationElementRef::Unknown { data, .. } => data.len() + 3,
+        }
+    }
+
+    /// Copies this information element into an owned `InformationElement`.
+    pub fn to_owned(&self) -> InformationElement {
+        match *self {
+            InformationElementRef::Header(header) => InformationElement::Header(header),
+            InformationElementRef::MOPayload(payload) => {
+                InformationElement::MOPayload(payload.to_vec())
+            }
+            InformationElementRef::MTPayload(payload) => {
+                InformationElement::MTPayload(payload.to_vec())
+            }
+            InformationElementRef::Status(status) => InformationElement::Status(status),
+            InformationElementRef::LocationInformation(location) => {
+                InformationElement::LocationInformation(location)
+            }
+            InformationElementRef::Priority(priority) => InformationElement::Priority(priority),
+            InformationElementRef::Unknown { iei, data } => InformationElement::Unknown {
+                iei,
+                data: data.to_vec(),
+            },
+        }
+    }
+}
+
+/// A Iridium SBD message borrowed from a byte slice.
+///
+/// Parsing only allocates for information elements besides the header, payload, location and
+/// priority, and for information elements longer than they need to be.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::message_ref::MessageRef;
+/// let data = std::fs::read("data/0-mo.sbd").unwrap();
+/// let message = MessageRef::parse(&data).unwrap();
+/// assert_eq!(b"test message from pete", message.payload());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MessageRef<'a> {
+    header: Header,
+    payload: Option<&'a [u8]>,
+    location: Option<LocationInformation>,
+    priority: Option<mt::MtPriority>,
+    information_elements: Vec<InformationElementRef<'a>>,
+    /// Where the header, payload, location and priority were found among the information
+    /// elements, in `IeSlot::CANONICAL` order.
+    positions: [Option<usize>; 4],
+    /// Bytes lenient parsing skipped past the length an information element requires, by
+    /// position among the information elements.
+    skipped: Vec<(usize, &'a [u8])>,
+}
+
+impl PartialEq for MessageRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.header == other.header
+            && self.payload == other.payload
+            && self.location == other.location
+            && self.priority == other.priority
+            && self.information_elements == other.information_elements
+    }
+}
+
+impl Eq for MessageRef<'_> {}
+
+impl<'a> MessageRef<'a> {
+    /// Parses a complete SBD frame, protocol header included.
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        Self::parse_with(data, ParseOptions::default())
+    }
+
+    /// Parses a complete SBD frame with the given options.
+    ///
+    /// Like `DirectIpMessage`, MO frames are accepted without a payload, since the gateway leaves
+    /// it out of sessions that carried no message.
+    pub fn parse_with(data: &'a [u8], options: ParseOptions) -> Result<Self> {
+        let mut header: Option<Header> = None;
+        let mut payload = None;
+        let mut location = None;
+        let mut priority = None;
+        let mut information_elements = Vec::new();
+        let mut positions = [None; 4];
+        let mut skipped = Vec::new();
+        // The first information element of each direction, to check against the header once it
+        // is found.
+        let mut first_mo = None;
+        let mut first_mt = None;
+        for (position, information_element) in FrameParser::new(data, options)?.enumerate() {
+            let (information_element, offset, skipped_bytes) = information_element?;
+            if !skipped_bytes.is_empty() {
+                skipped.push((position, skipped_bytes));
+            }
+            let iei = information_element.iei();
+            if options.is_strict() {
+                match is_mobile_originated(iei) {
+                    Some(true) => first_mo = first_mo.or(Some((iei, offset))),
+                    Some(false) => first_mt = first_mt.or(Some((iei, offset))),
+                    None => {}
+                }
+            }
+            let misplaced = |err| misplaced_error(err, iei, offset);
+            match information_element {
+                InformationElementRef::Header(h) => {
+                    if header.is_some() {
+                        return Err(misplaced(Error::TwoHeaders));
+                    } else {
+                        header = Some(h);
+                        positions[0] = Some(position);
+                    }
+                }
+                InformationElementRef::MOPayload(p) | InformationElementRef::MTPayload(p) => {
+                    if payload.is_some() {
+                        return Err(misplaced(Error::TwoPayloads));
+                    } else {
+                        payload = Some(p);
+                        positions[1] = Some(position);
+                    }
+                }
+                InformationElementRef::LocationInformation(l) => {
+                    if location.is_some() {
+                        return Err(misplaced(Error::TwoLocations));
+                    } else {
+                        location = Some(l);
+                        positions[2] = Some(position);
+                    }
+                }
+                InformationElementRef::Priority(p) => {
+                    if priority.is_some() {
+                        return Err(misplaced(Error::TwoPriorities));
+                    } else {
+                        priority = Some(p);
+                        positions[3] = Some(position);
+                    }
+                }
+                ie => information_elements.push(ie),
+            }
+        }
+
+        let header = header.ok_or_else(|| message_error(Error::NoHeader))?;
+        let (mobile_originated, mismatch) = match header {
+            Header::MOHeader(_) => (true, first_mt),
+            Header::MTHeader(_) => (false, first_mo),
+        };
+        if let Some((iei, offset)) = mismatch {
+            return Err(misplaced_error(
+                Error::DirectionMismatch {
+                    iei,
+                    mobile_originated,
+                },
+                iei,
+                offset,
+            ));
+        }
+        if let Header::MTHeader(header) = header {
+            if header.flags.requires_payload() && payload.is_none() {
+                return Err(message_error(Error::NoPayload));
+            }
+        }
+        Ok(MessageRef {
+            header,
+            payload,
+            location,
+            priority,
+            information_elements,
+            positions,
+            skipped,
+        })
+    }
+
+    /// Returns this message's header.
+    pub fn header(&self) -> &dyn SbdHeader {
+        &self.header
+    }
+
+    /// Returns this message's payload.
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload.unwrap_or_default()
+    }
+
+    /// Returns true if this message has a payload information element, possibly empty.
+    pub fn has_payload(&self) -> bool {
+        self.payload.is_some()
+    }
+
+    /// Returns this message's imei.
+    pub fn imei(&self) -> &Imei {
+        self.header.imei()
+    }
+
+    /// Returns this message's location.
+    pub fn location(&self) -> &Option<LocationInformation> {
+        &self.location
+    }
+
+    /// Returns this message's priority.
+    pub fn priority(&self) -> Option<mt::MtPriority> {
+        self.priority
+    }
+
+    /// Returns this message's other information elements.
+    pub fn information_elements(&self) -> &[InformationElementRef<'a>] {
+        &self.information_elements
+    }
+
+    /// Copies this message into an owned `Message`.
+    ///
+    /// The copy remembers the order of the information elements and the bytes lenient parsing
+    /// skipped, like a message read with `Message::read_from`.
+    pub fn to_owned(&self) -> Message {
+        let count = self.positions.iter().flatten().count() + self.information_elements.len();
+        let mut others = 0;
+        let order = (0..count)
+            .map(
+                |position| match self.positions.iter().position(|&p| p == Some(position)) {
+                    Some(index) => IeSlot::CANONICAL[index],
+                    None => {
+                        others += 1;
+                        IeSlot::Other(others - 1)
+                    }
+                },
+            )
+            .collect();
+        Message::from_parts(
+            self.header,
+            self.payload.map(<[u8]>::to_vec),
+            self.location,
+            self.priority,
+            self.information_elements
+                .iter()
+                .map(InformationElementRef::to_owned)
+                .collect(),
+            order,
+        )
+        .with_skipped(
+            self.skipped
+                .iter()
+                .map(|&(position, skipped)| (position, skipped.to_vec()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_as_owned() {
+        for path in &[
+            "data/0-mo.sbd",
+            "data/1-mo-location.sbd",
+            "data/data.sbd",
+            "data/iridium.ack",
+        ] {
+            let data = std::fs::read(path).unwrap();
+            let message = MessageRef::parse(&data).unwrap();
+            assert_eq!(
+                Message::from_path(path).unwrap(),
+                message.to_owned(),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn payload_borrows_input() {
+        let data = std::fs::read("data/0-mo.sbd").unwrap();
+        let message = MessageRef::parse(&data).unwrap();
+        assert_eq!(data[37..].as_ptr(), message.payload().as_ptr());
+    }
+
+    #[test]
+    fn information_elements() {
+        let data = std::fs::read("data/resp.sbd").unwrap();
+        let information_elements = InformationElementRef::parse(&data).unwrap();
+        assert_eq!(
+            InformationElement::parse(data.as_slice()).unwrap(),
+            information_elements
+                .iter()
+                .map(InformationElementRef::to_owned)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn mo_without_payload() {
+        let data = std::fs::read("data/iridium.ack").unwrap();
+        let message = MessageRef::parse(&data).unwrap();
+        assert!(!message.has_payload());
+        assert!(message.location().is_some());
+    }
+
+    #[test]
+    fn direction_before_header() {
+        let information_elements: Vec<InformationElement> = vec![
+            LocationInformation::new(0, (43, 30854), (41, 48860), None).into(),
+            mt::Header {
+                message_id: 1,
+                imei: (*b"300434060009290").into(),
+                flags: mt::DispositionFlags::empty(),
+            }
+            .into(),
+            InformationElement::MTPayload(b"reboot".to_vec()),
+        ];
+        let mut data = vec![1, 0, 0];
+        for information_element in &information_elements {
+            information_element.write_to(&mut data).unwrap();
+        }
+        data[2] = (data.len() - 3) as u8;
+
+        assert!(MessageRef::parse(&data).is_ok());
+        let err = MessageRef::parse_with(&data, ParseOptions::strict()).unwrap_err();
+        assert!(matches!(
+            err.root(),
+            Error::DirectionMismatch {
+                iei: 0x03,
+                mobile_originated: false
+            }
+        ));
+        assert_eq!(
+            (Some(3), Some(0x03), Some("iei")),
+            (err.offset(), err.iei(), err.field())
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let data = std::fs::read("data/1-mo-location.sbd").unwrap();
+        for len in 0..data.len() {
+            assert!(MessageRef::parse(&data[..len]).is_err(), "{}", len);
+        }
+    }
+
+    #[test]
+    fn invalid_protocol_revision() {
+        let mut data = std::fs::read("data/0-mo.sbd").unwrap();
+        data[0] = 2;
+        let err = MessageRef::parse(&data).unwrap_err();
+        assert!(matches!(
+            err.root(),
+            Error::InvalidProtocolRevisionNumber(2)
+        ));
+        assert_eq!(
+            (Some(0), None, Some("protocol_revision_number")),
+            (err.offset(), err.iei(), err.field())
+        );
+    }
+
+    fn assert_truncated_at(path: &str, expected: &[(usize, usize, Option<u8>, &str)]) {
+        let data = std::fs::read(path).unwrap();
+        for &(len, offset, iei, field) in expected {
+            let errors = [
+                MessageRef::parse(&data[..len]).unwrap_err(),
+                Message::read_from(&data[..len]).unwrap_err(),
+            ];
+            for err in &errors {
+                assert!(
+                    matches!(err.root(), Error::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof),
+                    "{}: {}",
+                    len,
+                    err
+                );
+                assert_eq!(
+                    (Some(offset), iei, Some(field)),
+                    (err.offset(), err.iei(), err.field()),
+                    "{}",
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_offsets() {
+        assert_truncated_at(
+            "data/0-mo.sbd",
+            &[
+                (0, 0, None, "protocol_revision_number"),
+                (2, 1, None, "overall_message_length"),
+                (3, 3, None, "iei"),
+                (4, 4, Some(0x01), "length"),
+                (20, 6, Some(0x01), "value"),
+                (34, 34, None, "iei"),
+                (36, 35, Some(0x02), "length"),
+                (58, 37, Some(0x02), "value"),
+            ],
+        );
+        assert_truncated_at(
+            "data/1-mo-location.sbd",
+            &[
+                (33, 6, Some(0x01), "value"),
+                (35, 35, Some(0x03), "length"),
+                (40, 37, Some(0x03), "value"),
+                (48, 48, None, "iei"),
+                (55, 51, Some(0x02), "value"),
+            ],
+        );
+    }
+
+    #[test]
+    fn value_offsets() {
+        let mut data = std::fs::read("data/1-mo-location.sbd").unwrap();
+        data[25] = 3;
+        data[37] = 0x10;
+        let err = MessageRef::parse_with(&data, ParseOptions::strict()).unwrap_err();
+        assert!(matches!(err.root(), Error::UnknownSessionStatus(3)));
+        assert_eq!((Some(25), Some(0x01)), (err.offset(), err.iei()));
+        assert_eq!(
+            "unknown session status 3 at offset 25 (session_status of information element 0x01)",
+            err.to_string()
+        );
+        assert!(std::error::Error::source(&err).is_none());
+
+        data[25] = 0;
+        let err = MessageRef::parse_with(&data, ParseOptions::strict()).unwrap_err();
+        assert!(matches!(err.root(), Error::UnsupportedLocationFormat(1)));
+        assert_eq!(
+            (Some(37), Some(0x03), Some("flags")),
+            (err.offset(), err.iei(), err.field())
+        );
+    }
+}
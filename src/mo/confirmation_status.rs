@@ -15,10 +15,16 @@ pub struct ConfirmationStatus {
 }
 
 impl ConfirmationStatus {
+    /// Creates a new confirmation, `true` meaning the message was received successfully.
+    pub fn new(status: bool) -> Self {
+        ConfirmationStatus { status }
+    }
+
     pub fn status(&self) -> bool {
         self.status
     }
 
+    /// Reads the confirmation value, following the IEI and length.
     pub fn read_from(read: &mut dyn Read) -> Result<Self> {
         use crate::Error;
         use byteorder::ReadBytesExt;
@@ -28,12 +34,86 @@ impl ConfirmationStatus {
         Ok(ConfirmationStatus { status })
     }
 
+    /// Writes the whole confirmation information element, IEI and length included.
     pub fn write_to<W: Write>(&self, write: &mut W) -> Result<()> {
+        use byteorder::BigEndian;
+
+        write.write_u8(0x05)?;
+        write.write_u16::<BigEndian>(1)?;
         write.write_u8(self.status as u8)?;
         Ok(())
     }
 
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         4
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DirectIpMessage, Error, InformationElement, ParseOptions};
+
+    #[test]
+    fn write_to() {
+        let mut buff = vec![];
+        ConfirmationStatus::new(true).write_to(&mut buff).unwrap();
+        assert_eq!(vec![0x05, 0, 1, 1], buff);
+        assert_eq!(ConfirmationStatus::new(true).len(), buff.len());
+    }
+
+    #[test]
+    fn roundtrip() {
+        for status in &[true, false] {
+            let ie = InformationElement::from(ConfirmationStatus::new(*status));
+            let mut buff = vec![];
+            ie.write_to(&mut buff).unwrap();
+            assert_eq!(
+                ie,
+                InformationElement::read_single(buff.as_slice()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn length_mismatch() {
+        let data = [0x05, 0, 2, 1, 0];
+        assert!(matches!(
+            InformationElement::read_single_with(&data[..], ParseOptions::strict())
+                .as_ref()
+                .map_err(Error::root),
+            Err(Error::IeLengthMismatch {
+                iei: 0x05,
+                declared: 2,
+                expected: 1
+            })
+        ));
+        assert_eq!(
+            InformationElement::from(ConfirmationStatus::new(true)),
+            InformationElement::read_single(&data[..]).unwrap()
+        );
+        assert!(matches!(
+            InformationElement::read_single(&[0x05, 0, 0][..])
+                .as_ref()
+                .map_err(Error::root),
+            Err(Error::IeLengthMismatch {
+                iei: 0x05,
+                declared: 0,
+                expected: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn confirmation_fixture() {
+        let data = std::fs::read("data/mo-confirmation.sbd").unwrap();
+        let message = DirectIpMessage::read_from(data.as_slice()).unwrap();
+        assert_eq!(DirectIpMessage::mo_confirmation(true), message);
+
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+        assert_eq!(vec![1, 0, 4, 0x05, 0, 1, 1], buff);
+        assert_eq!(data, buff);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::information_element::SbdHeader;
 use crate::mo::session_status::SessionStatus;
-use crate::{Imei, Result};
+use crate::{Imei, ParseOptions, Result};
 #[cfg(feature = "serde-derive")]
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
@@ -39,7 +39,7 @@ impl SbdHeader for Header {
         write.write_u16::<BigEndian>(28)?;
         write.write_u32::<BigEndian>(self.auto_id)?;
         write.write_all(&self.imei)?;
-        write.write_u8(self.session_status as u8)?;
+        write.write_u8(self.session_status.into())?;
         write.write_u16::<BigEndian>(self.momsn)?;
         write.write_u16::<BigEndian>(self.mtmsn)?;
         let timestamp = self.time_of_session.unix_timestamp();
@@ -51,27 +51,37 @@ impl SbdHeader for Header {
         Ok(())
     }
 
-    fn imei(&self) -> &str {
-        use std::str;
-        str::from_utf8(&self.imei).expect("IMEI numbers are specified to be ascii number")
+    fn imei(&self) -> &Imei {
+        &self.imei
     }
     fn len(&self) -> usize {
         31
     }
     fn as_mo(&self) -> Option<&Header> {
-        Some(&self)
+        Some(self)
     }
 }
 
 impl Header {
     pub fn read_from(read: &mut dyn Read) -> Result<Self> {
+        Self::read_from_with(read, ParseOptions::default())
+    }
+
+    /// Reads the header with the given options, following the IEI and length.
+    pub fn read_from_with(read: &mut dyn Read, options: ParseOptions) -> Result<Self> {
         use crate::Error;
         use byteorder::{BigEndian, ReadBytesExt};
 
         let auto_id = read.read_u32::<BigEndian>().map_err(Error::Io)?;
         let mut imei = [0; 15];
         read.read_exact(&mut imei).map_err(Error::Io)?;
-        let session_status = SessionStatus::new(read.read_u8().map_err(Error::Io)?)?;
+        options.check_imei(imei.into())?;
+        let session_status = read.read_u8().map_err(Error::Io)?;
+        let session_status = if options.is_strict() {
+            SessionStatus::new(session_status)?
+        } else {
+            SessionStatus::from(session_status)
+        };
         let momsn = read.read_u16::<BigEndian>().map_err(Error::Io)?;
         let mtmsn = read.read_u16::<BigEndian>().map_err(Error::Io)?;
         let time_of_session = read.read_u32::<BigEndian>().map_err(Error::from).map(|n| {
@@ -1,4 +1,4 @@
-use crate::Result;
+use crate::{Error, Result};
 #[cfg(feature = "serde-derive")]
 use serde::{Deserialize, Serialize};
 use std::{fmt, io::Write};
@@ -13,8 +13,9 @@ pub enum LocationDirection {
 }
 
 impl From<u8> for LocationDirection {
+    /// Decodes the direction bits of a location flags byte, ignoring the other bits.
     fn from(flag: u8) -> Self {
-        match flag {
+        match flag & LocationFlags::DIRECTION {
             0x40 => LocationDirection::SE,
             0x80 => LocationDirection::NW,
             0xC0 => LocationDirection::SW,
@@ -34,10 +35,115 @@ impl From<LocationDirection> for u8 {
     }
 }
 
+/// The flags byte at the start of the location information.
+///
+/// It holds the hemisphere bits, a format code and reserved bits. The raw byte is kept so it is
+/// written back exactly as received.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::mo::{LocationDirection, LocationFlags};
+/// let flags = LocationFlags::from_bits(0x41);
+/// assert_eq!(LocationDirection::SE, flags.direction());
+/// assert_eq!(0, flags.format_code());
+/// assert_eq!(0x01, flags.reserved());
+/// assert_eq!(0x41, flags.bits());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde-derive",
+    derive(Serialize, Deserialize),
+    serde(into = "u8", from = "SerdeFlags")
+)]
+pub struct LocationFlags(u8);
+
+/// Flags are serialized as their raw byte, but a direction name as written by earlier versions
+/// is still accepted.
+#[cfg(feature = "serde-derive")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SerdeFlags {
+    Bits(u8),
+    Direction(LocationDirection),
+}
+
+#[cfg(feature = "serde-derive")]
+impl From<SerdeFlags> for LocationFlags {
+    fn from(flags: SerdeFlags) -> Self {
+        match flags {
+            SerdeFlags::Bits(bits) => LocationFlags(bits),
+            SerdeFlags::Direction(direction) => direction.into(),
+        }
+    }
+}
+
+impl LocationFlags {
+    const RESERVED: u8 = 0x0F;
+    const FORMAT_CODE: u8 = 0x30;
+    const SOUTH: u8 = 0x40;
+    const WEST: u8 = 0x80;
+    const DIRECTION: u8 = Self::SOUTH | Self::WEST;
+
+    /// Creates flags from the raw byte, keeping every bit.
+    pub const fn from_bits(bits: u8) -> Self {
+        LocationFlags(bits)
+    }
+
+    /// Returns the raw byte.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns the format code, only format 0 is documented.
+    pub const fn format_code(self) -> u8 {
+        (self.0 & Self::FORMAT_CODE) >> 4
+    }
+
+    /// Returns the reserved bits that are set.
+    pub const fn reserved(self) -> u8 {
+        self.0 & Self::RESERVED
+    }
+
+    /// Returns true if the latitude is in the southern hemisphere.
+    pub const fn south(self) -> bool {
+        self.0 & Self::SOUTH != 0
+    }
+
+    /// Returns true if the longitude is in the western hemisphere.
+    pub const fn west(self) -> bool {
+        self.0 & Self::WEST != 0
+    }
+
+    /// Returns the hemispheres of the location.
+    pub fn direction(self) -> LocationDirection {
+        self.0.into()
+    }
+}
+
+impl From<LocationDirection> for LocationFlags {
+    fn from(direction: LocationDirection) -> Self {
+        LocationFlags(direction.into())
+    }
+}
+
+impl From<u8> for LocationFlags {
+    fn from(bits: u8) -> Self {
+        LocationFlags(bits)
+    }
+}
+
+impl From<LocationFlags> for u8 {
+    fn from(flags: LocationFlags) -> Self {
+        flags.bits()
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
 pub struct LocationInformation {
-    direction: LocationDirection,
+    #[cfg_attr(feature = "serde-derive", serde(rename = "direction"))]
+    flags: LocationFlags,
     latitude: (u8, u16),
     longitude: (u8, u16),
     radius: Option<u32>,
@@ -48,7 +154,7 @@ impl fmt::Display for LocationInformation {
         write!(
             f,
             "direction: {:?}, latitude: {:.7}, longitude: {:.7} radius: {} km",
-            self.direction,
+            self.direction(),
             self.latitude(),
             self.longitude(),
             self.radius.unwrap_or(0xffff)
@@ -63,31 +169,108 @@ impl LocationInformation {
         longitude: (u8, u16),
         radius: Option<u32>,
     ) -> LocationInformation {
-        let direction = flags.into();
         Self {
-            direction,
+            flags: flags.into(),
             latitude,
             longitude,
             radius,
         }
     }
 
+    /// Creates a location from signed decimal degrees, negative values being south and west.
+    ///
+    /// The coordinates are rounded to the thousandth of a minute used on the wire. Returns an
+    /// error if the latitude is outside of ±90° or the longitude outside of ±180°.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sbd_lib::mo::{LocationDirection, LocationInformation};
+    /// let location = LocationInformation::from_degrees(-33.8568, 151.2153, Some(5)).unwrap();
+    /// assert_eq!(LocationDirection::SE, location.direction());
+    /// assert!((location.signed_latitude() + 33.8568).abs() < 1e-5);
+    /// assert!(LocationInformation::from_degrees(91.0, 0.0, None).is_err());
+    /// ```
+    pub fn from_degrees(latitude: f64, longitude: f64, cep_km: Option<u32>) -> Result<Self> {
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(Error::InvalidCoordinates {
+                latitude,
+                longitude,
+            });
+        }
+        let direction = match (latitude < 0.0, longitude < 0.0) {
+            (false, false) => LocationDirection::NE,
+            (true, false) => LocationDirection::SE,
+            (false, true) => LocationDirection::NW,
+            (true, true) => LocationDirection::SW,
+        };
+        Ok(Self {
+            flags: direction.into(),
+            latitude: Self::degrees_and_minutes(latitude),
+            longitude: Self::degrees_and_minutes(longitude),
+            radius: cep_km,
+        })
+    }
+
+    /// Splits an angle into whole degrees and thousandths of a minute.
+    fn degrees_and_minutes(angle: f64) -> (u8, u16) {
+        let angle = angle.abs();
+        let degrees = angle.trunc();
+        let minutes = ((angle - degrees) * 60_000.0).round();
+        if minutes >= 60_000.0 {
+            (degrees as u8 + 1, 0)
+        } else {
+            (degrees as u8, minutes as u16)
+        }
+    }
+
+    /// Returns the unsigned latitude in decimal degrees, see `direction` for the hemisphere.
     pub fn latitude(&self) -> f64 {
         (f64::from(self.latitude.0) * 10_000_000.0f64
             + (f64::from(self.latitude.1) * 10000.0) / 60.0)
             * 1e-7
     }
 
+    /// Returns the unsigned longitude in decimal degrees, see `direction` for the hemisphere.
     pub fn longitude(&self) -> f64 {
         (f64::from(self.longitude.0) * 10_000_000.0f64
             + (f64::from(self.longitude.1) * 10000.0) / 60.0)
             * 1e-7
     }
 
+    /// Returns the latitude in decimal degrees, negative in the southern hemisphere.
+    pub fn signed_latitude(&self) -> f64 {
+        if self.flags.south() {
+            -self.latitude()
+        } else {
+            self.latitude()
+        }
+    }
+
+    /// Returns the longitude in decimal degrees, negative in the western hemisphere.
+    pub fn signed_longitude(&self) -> f64 {
+        if self.flags.west() {
+            -self.longitude()
+        } else {
+            self.longitude()
+        }
+    }
+
     pub fn direction(&self) -> LocationDirection {
-        self.direction
+        self.flags.direction()
+    }
+
+    /// Returns the flags byte as received.
+    pub fn flags(&self) -> LocationFlags {
+        self.flags
     }
 
+    /// Returns the CEP radius in kilometers, if present.
+    pub fn radius(&self) -> Option<u32> {
+        self.radius
+    }
+
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         match self.radius {
             None => 10,
@@ -97,11 +280,9 @@ impl LocationInformation {
 
     pub fn write_to<W: Write>(&self, write: &mut W) -> Result<()> {
         use byteorder::{BigEndian, WriteBytesExt};
-        // let flag: u8 =
-        //     if self.latitude > 0 { 0 } else { 1 } | if self.longitude > 0 { 0 } else { 1 << 1 };
         write.write_u8(3)?;
         write.write_u16::<BigEndian>((self.len() - 3) as u16)?;
-        write.write_u8(self.direction.into())?;
+        write.write_u8(self.flags.bits())?;
 
         write.write_u8(self.latitude.0)?;
 
@@ -110,10 +291,9 @@ impl LocationInformation {
         write.write_u8(self.longitude.0)?;
 
         write.write_u16::<BigEndian>(self.longitude.1)?;
-        match self.radius {
-            Some(radius) => write.write_u32::<BigEndian>(radius)?,
-            _ => {}
-        };
+        if let Some(radius) = self.radius {
+            write.write_u32::<BigEndian>(radius)?;
+        }
 
         Ok(())
     }
@@ -141,4 +321,174 @@ mod tests {
         let loc = LocationInformation::new(0xC0, (43, 30854), (41, 48860), Some(3));
         assert_eq!(loc.direction(), LocationDirection::SW);
     }
+
+    #[test]
+    fn flags() {
+        for bits in 0..=u8::MAX {
+            let loc = LocationInformation::new(bits, (43, 30854), (41, 48860), None);
+            assert_eq!(bits, loc.flags().bits());
+            assert_eq!(bits & 0x40 != 0, loc.signed_latitude() < 0.0);
+            assert_eq!(bits & 0x80 != 0, loc.signed_longitude() < 0.0);
+
+            let mut buff = vec![];
+            loc.write_to(&mut buff).unwrap();
+            assert_eq!(bits, buff[3]);
+        }
+        let flags = LocationFlags::from_bits(0xB5);
+        assert_eq!(
+            (3, 0x05, false, true),
+            (
+                flags.format_code(),
+                flags.reserved(),
+                flags.south(),
+                flags.west()
+            )
+        );
+        assert_eq!(LocationDirection::NW, flags.direction());
+    }
+
+    #[test]
+    fn strict_parsing() {
+        use crate::{InformationElement, ParseOptions};
+
+        let strict = ParseOptions::strict();
+        let data = [0x03, 0, 7, 0x55, 43, 0x78, 0x86, 41, 0xbe, 0xdc];
+        let ie = InformationElement::read_single(&data[..]).unwrap();
+        let mut buff = vec![];
+        ie.write_to(&mut buff).unwrap();
+        assert_eq!(&data[..], buff.as_slice());
+        assert!(matches!(
+            InformationElement::read_single_with(&data[..], strict)
+                .as_ref()
+                .map_err(Error::root),
+            Err(Error::UnsupportedLocationFormat(1))
+        ));
+
+        let mut data = data.to_vec();
+        data[3] = 0x40;
+        assert!(InformationElement::read_single_with(data.as_slice(), strict).is_ok());
+        for declared in 8..=10 {
+            data[2] = declared;
+            data.push(0);
+            assert!(matches!(
+                InformationElement::read_single(data.as_slice())
+                    .as_ref()
+                    .map_err(Error::root),
+                Err(Error::IeLengthMismatch {
+                    iei: 0x03,
+                    declared: d,
+                    expected: 11
+                }) if *d == u16::from(declared)
+            ));
+        }
+        data[2] = 12;
+        data.extend_from_slice(&[3, 0]);
+        let ie = InformationElement::read_single(data.as_slice()).unwrap();
+        assert_eq!(
+            InformationElement::LocationInformation(LocationInformation::new(
+                0x40,
+                (43, 0x7886),
+                (41, 0xbedc),
+                Some(3)
+            )),
+            ie
+        );
+        assert!(matches!(
+            InformationElement::read_single_with(data.as_slice(), strict)
+                .as_ref()
+                .map_err(Error::root),
+            Err(Error::IeLengthMismatch {
+                iei: 0x03,
+                declared: 12,
+                expected: 11
+            })
+        ));
+
+        let file = std::fs::File::open("data/1-mo-location.sbd").unwrap();
+        assert!(crate::Message::read_from_with(file, strict).is_ok());
+    }
+
+    #[cfg(feature = "serde-derive")]
+    #[test]
+    fn deserialize_flags() {
+        use serde::de::{value, Deserialize, IntoDeserializer};
+
+        let flags: std::result::Result<LocationFlags, value::Error> =
+            LocationFlags::deserialize(IntoDeserializer::into_deserializer(0x41u8));
+        assert_eq!(LocationFlags::from_bits(0x41), flags.unwrap());
+        let flags: std::result::Result<LocationFlags, value::Error> =
+            LocationFlags::deserialize(IntoDeserializer::into_deserializer("SW"));
+        assert_eq!(LocationFlags::from_bits(0xC0), flags.unwrap());
+    }
+
+    #[test]
+    fn signed() {
+        let loc = LocationInformation::new(0xC0, (43, 30854), (41, 48860), Some(3));
+        assert_eq!(loc.signed_latitude(), -43.51423333333333);
+        assert_eq!(loc.signed_longitude(), -41.81433333333333);
+        let loc = LocationInformation::new(0x80, (43, 30854), (41, 48860), Some(3));
+        assert_eq!(loc.signed_latitude(), 43.51423333333333);
+        assert_eq!(loc.signed_longitude(), -41.81433333333333);
+    }
+
+    #[test]
+    fn roundtrip_degrees() {
+        let points = [
+            (0.0, 0.0),
+            (0.0, 179.99999),
+            (0.0, 180.0),
+            (0.0, -180.0),
+            (90.0, 0.0),
+            (-90.0, 0.0),
+            (-90.0, -180.0),
+            (51.4778, -0.0015),
+            (-33.8568, 151.2153),
+            (-0.5, -0.25),
+        ];
+        for &(latitude, longitude) in &points {
+            let loc = LocationInformation::from_degrees(latitude, longitude, Some(2)).unwrap();
+            assert!(
+                (loc.signed_latitude() - latitude).abs() < 1e-5,
+                "{}",
+                latitude
+            );
+            assert!(
+                (loc.signed_longitude() - longitude).abs() < 1e-5,
+                "{}",
+                longitude
+            );
+
+            let mut buff = vec![];
+            loc.write_to(&mut buff).unwrap();
+            let read_back = crate::InformationElement::read_single(buff.as_slice()).unwrap();
+            assert_eq!(
+                crate::InformationElement::LocationInformation(loc),
+                read_back
+            );
+        }
+    }
+
+    #[test]
+    fn minutes_carry() {
+        let loc = LocationInformation::from_degrees(10.999999, -0.0, None).unwrap();
+        assert_eq!((11, 0), loc.latitude);
+        assert_eq!(LocationDirection::NE, loc.direction());
+    }
+
+    #[test]
+    fn out_of_range() {
+        for &(latitude, longitude) in &[
+            (90.001, 0.0),
+            (-90.001, 0.0),
+            (0.0, 180.001),
+            (0.0, -180.001),
+            (f64::NAN, 0.0),
+            (0.0, f64::INFINITY),
+        ] {
+            assert!(matches!(
+                LocationInformation::from_degrees(latitude, longitude, None),
+                Err(Error::InvalidCoordinates { .. })
+            ));
+        }
+    }
 }
@@ -0,0 +1,330 @@
+use crate::mo::{Header, LocationInformation, SessionStatus};
+use crate::{Error, Imei, InformationElement, Message, Result};
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, io::Write};
+use time::OffsetDateTime;
+
+/// The largest MO payload the gateway accepts.
+pub const MAX_PAYLOAD_LEN: usize = 1960;
+
+/// A mobile-originated message, received from the gateway.
+///
+/// Unlike `Message`, it can only hold what belongs to a mobile-originated message. The payload
+/// is optional: the gateway omits it for sessions that carried no message.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use sbd_lib::mo::MoMessage;
+/// use sbd_lib::Message;
+/// let message = MoMessage::try_from(Message::from_path("data/1-mo-location.sbd").unwrap()).unwrap();
+/// assert_eq!(Some(&b"hello"[..]), message.payload());
+/// assert!(message.location().is_some());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+pub struct MoMessage {
+    header: Header,
+    payload: Option<Vec<u8>>,
+    location: Option<LocationInformation>,
+    information_elements: Vec<InformationElement>,
+}
+
+impl MoMessage {
+    /// Returns a builder for a new message.
+    pub fn builder() -> MoMessageBuilder {
+        MoMessageBuilder::default()
+    }
+
+    /// Returns this message's header.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns this message's imei.
+    pub fn imei(&self) -> &Imei {
+        &self.header.imei
+    }
+
+    /// Returns this message's payload, if the session carried one.
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.payload.as_deref()
+    }
+
+    /// Returns this message's location.
+    pub fn location(&self) -> Option<LocationInformation> {
+        self.location
+    }
+
+    /// Returns the information elements this library does not know.
+    pub fn information_elements(&self) -> &[InformationElement] {
+        &self.information_elements
+    }
+
+    /// Writes this message to a `Write`.
+    pub fn write_to<W: Write>(&self, write: W) -> Result<()> {
+        Message::from(self.clone()).write_to(write)
+    }
+}
+
+impl From<MoMessage> for Message {
+    fn from(message: MoMessage) -> Self {
+        Message::from_parts(
+            message.header.into(),
+            message.payload,
+            message.location,
+            None,
+            message.information_elements,
+            Vec::new(),
+        )
+    }
+}
+
+impl TryFrom<Message> for MoMessage {
+    type Error = Error;
+
+    /// Converts a message with a mobile-originated header.
+    ///
+    /// The message is checked like `MoMessageBuilder::build` does, and must not hold any
+    /// information element of mobile-terminated messages.
+    fn try_from(message: Message) -> Result<Self> {
+        let (header, payload, location, priority, information_elements) = message.into_parts();
+        let header = match header {
+            crate::Header::MOHeader(header) => header,
+            crate::Header::MTHeader(_) => return Err(Error::UnexpectedMessage),
+        };
+        if priority.is_some() {
+            return Err(Error::DirectionMismatch {
+                iei: 0x46,
+                mobile_originated: true,
+            });
+        }
+        crate::message_ref::check_directions(
+            &header.into(),
+            information_elements.iter().map(InformationElement::iei),
+        )
+        .map_err(|(_, err)| err)?;
+        validate(&header, payload.as_deref())?;
+        Ok(MoMessage {
+            header,
+            payload,
+            location,
+            information_elements,
+        })
+    }
+}
+
+/// Builds a `MoMessage`, e.g. to simulate the gateway.
+#[derive(Clone, Debug, Default)]
+pub struct MoMessageBuilder {
+    auto_id: u32,
+    imei: Option<Imei>,
+    session_status: Option<SessionStatus>,
+    momsn: u16,
+    mtmsn: u16,
+    time_of_session: Option<OffsetDateTime>,
+    payload: Option<Vec<u8>>,
+    location: Option<LocationInformation>,
+}
+
+impl MoMessageBuilder {
+    /// Sets the Iridium Gateway id, 0 by default.
+    pub fn auto_id(mut self, auto_id: u32) -> Self {
+        self.auto_id = auto_id;
+        self
+    }
+
+    /// Sets the device id, which is required.
+    pub fn imei(mut self, imei: Imei) -> Self {
+        self.imei = Some(imei);
+        self
+    }
+
+    /// Sets the session status, `SessionStatus::Ok` by default.
+    pub fn session_status(mut self, session_status: SessionStatus) -> Self {
+        self.session_status = Some(session_status);
+        self
+    }
+
+    /// Sets the mobile originated message sequence number, 0 by default.
+    pub fn momsn(mut self, momsn: u16) -> Self {
+        self.momsn = momsn;
+        self
+    }
+
+    /// Sets the mobile terminated message sequence number, 0 by default.
+    pub fn mtmsn(mut self, mtmsn: u16) -> Self {
+        self.mtmsn = mtmsn;
+        self
+    }
+
+    /// Sets the time of the Iridium session, which is required.
+    pub fn time_of_session(mut self, time_of_session: OffsetDateTime) -> Self {
+        self.time_of_session = Some(time_of_session);
+        self
+    }
+
+    /// Sets the payload.
+    pub fn payload<P: Into<Vec<u8>>>(mut self, payload: P) -> Self {
+        self.payload = Some(payload.into());
+        self
+    }
+
+    /// Sets the location.
+    pub fn location(mut self, location: LocationInformation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Builds the message.
+    ///
+    /// Returns an error if a required field is missing, the IMEI is not numeric, the time of
+    /// session cannot be encoded or the payload is larger than the gateway sends.
+    pub fn build(self) -> Result<MoMessage> {
+        let imei = self.imei.ok_or(Error::MissingField("imei"))?;
+        let time_of_session = self
+            .time_of_session
+            .ok_or(Error::MissingField("time_of_session"))?;
+        let header = Header {
+            auto_id: self.auto_id,
+            imei,
+            session_status: self.session_status.unwrap_or(SessionStatus::Ok),
+            momsn: self.momsn,
+            mtmsn: self.mtmsn,
+            time_of_session,
+        };
+        validate(&header, self.payload.as_deref())?;
+        Ok(MoMessage {
+            header,
+            payload: self.payload,
+            location: self.location,
+            information_elements: Vec::new(),
+        })
+    }
+}
+
+/// Checks the IMEI is numeric, the time of session can be encoded and the payload is not larger
+/// than the gateway sends.
+fn validate(header: &Header, payload: Option<&[u8]>) -> Result<()> {
+    if !header.imei.is_numeric() {
+        return Err(Error::InvalidImei(header.imei));
+    }
+    let timestamp = header.time_of_session.unix_timestamp();
+    if timestamp < 0 {
+        return Err(Error::NegativeTimestamp(timestamp));
+    } else if timestamp > i64::from(u32::MAX) {
+        return Err(Error::InvalidTimeRange(timestamp));
+    }
+    match payload {
+        Some(payload) if payload.len() > MAX_PAYLOAD_LEN => Err(Error::PayloadTooLarge {
+            length: payload.len(),
+            max: MAX_PAYLOAD_LEN,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_as_fixture() {
+        let expected = Message::from_path("data/1-mo-location.sbd").unwrap();
+        let header = *expected.header().as_mo().unwrap();
+        let message = MoMessage::builder()
+            .auto_id(header.auto_id)
+            .imei(header.imei)
+            .session_status(header.session_status)
+            .momsn(header.momsn)
+            .mtmsn(header.mtmsn)
+            .time_of_session(header.time_of_session)
+            .location(expected.location().unwrap())
+            .payload(&b"hello"[..])
+            .build()
+            .unwrap();
+        assert_eq!(message, MoMessage::try_from(expected).unwrap());
+
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+        assert_eq!(
+            Message::from_path("data/1-mo-location.sbd").unwrap(),
+            Message::read_from(buff.as_slice()).unwrap()
+        );
+    }
+
+    #[test]
+    fn without_payload() {
+        let message = MoMessage::builder()
+            .imei((*b"300234063904190").into())
+            .time_of_session(OffsetDateTime::UNIX_EPOCH)
+            .build()
+            .unwrap();
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+
+        let read_back = Message::read_from(buff.as_slice()).unwrap();
+        assert!(!read_back.has_payload());
+        assert_eq!(
+            read_back,
+            crate::MessageRef::parse(&buff).unwrap().to_owned()
+        );
+        assert_eq!(message, MoMessage::try_from(read_back).unwrap());
+    }
+
+    #[test]
+    fn try_from_checks() {
+        let message = Message::from_path("data/0-mo.sbd").unwrap();
+        let header = *message.header().as_mo().unwrap();
+        let with = |payload: Vec<u8>, information_elements: Vec<InformationElement>| {
+            MoMessage::try_from(Message::new(
+                header.into(),
+                payload,
+                None,
+                information_elements,
+            ))
+        };
+        assert!(matches!(
+            with(vec![0; MAX_PAYLOAD_LEN + 1], vec![]),
+            Err(Error::PayloadTooLarge { .. })
+        ));
+        let confirmation = crate::mt::ConfirmationStatus {
+            message_id: 1,
+            imei: header.imei,
+            auto_id: 1,
+            status: crate::mt::MtStatus::NoPayload,
+        };
+        assert!(matches!(
+            with(b"hello".to_vec(), vec![confirmation.into()]),
+            Err(Error::DirectionMismatch {
+                iei: 0x44,
+                mobile_originated: true
+            })
+        ));
+        assert!(with(b"hello".to_vec(), vec![]).is_ok());
+    }
+
+    #[test]
+    fn invalid() {
+        let builder = MoMessage::builder().imei((*b"300234063904190").into());
+        assert!(matches!(
+            builder.clone().build(),
+            Err(Error::MissingField("time_of_session"))
+        ));
+        assert!(matches!(
+            builder
+                .clone()
+                .time_of_session(OffsetDateTime::UNIX_EPOCH - time::Duration::seconds(1))
+                .build(),
+            Err(Error::NegativeTimestamp(-1))
+        ));
+        let builder = builder.time_of_session(OffsetDateTime::UNIX_EPOCH);
+        assert_eq!(None, builder.clone().build().unwrap().payload());
+        assert!(matches!(
+            builder.payload(vec![0; MAX_PAYLOAD_LEN + 1]).build(),
+            Err(Error::PayloadTooLarge { .. })
+        ));
+    }
+}
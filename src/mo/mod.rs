@@ -1,9 +1,11 @@
 mod confirmation_status;
 mod header;
 mod location_information;
+mod message;
 mod session_status;
 
 pub use confirmation_status::ConfirmationStatus;
 pub use header::Header;
-pub use location_information::LocationInformation;
-pub use session_status::SessionStatus;
+pub use location_information::{LocationDirection, LocationFlags, LocationInformation};
+pub use message::{MoMessage, MoMessageBuilder, MAX_PAYLOAD_LEN};
+pub use session_status::{SessionStatus, UnknownStatusCode};
@@ -1,4 +1,4 @@
-use crate::Result;
+use crate::{Error, Result};
 #[cfg(feature = "serde-derive")]
 use serde::{Deserialize, Serialize};
 
@@ -9,31 +9,85 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
 pub enum SessionStatus {
     /// The SBD session completed successfully.
-    Ok = 0,
+    Ok,
     /// The MO message transfer, if any, was successful. The MT message queued at the GSS is too
     /// large to be transferred within a single SBD session.
-    OkMobileTerminatedTooLarge = 1,
+    OkMobileTerminatedTooLarge,
     /// The MO message transfer, if any, was successful. The reported location was determined to be
     /// of unacceptable quality. This value is only applicable to IMEIs using SBD protocol revision
     /// 1.
-    OkLocationUnacceptableQuality = 2,
+    OkLocationUnacceptableQuality,
     /// The SBD session timed out before session completion.
-    Timeout = 10,
+    Timeout,
     /// The MO message being transferred by the IMEI is too large to be transerred within a single
     /// SBD session.
-    MobileOriginatedTooLarge = 12,
+    MobileOriginatedTooLarge,
     /// An RF link loss ocurred during the SBD session.
-    RFLinkLoss = 13,
+    RFLinkLoss,
     /// An IMEI protocol anomaly occurred during SBD session.
-    IMEIProtocolAnomaly = 14,
+    IMEIProtocolAnomaly,
     /// The IMEI is prohibited from accessing the GSS.
-    Prohibited = 15,
+    Prohibited,
+    /// A status code not documented by `DirectIP`.
+    Unknown(UnknownStatusCode),
+}
+
+/// A session status code not documented by `DirectIP`.
+///
+/// It can only hold codes without a named `SessionStatus`, so a status reads back as the same
+/// variant it was written from.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::mo::{SessionStatus, UnknownStatusCode};
+/// let code = UnknownStatusCode::new(3).unwrap();
+/// assert_eq!(3, code.code());
+/// assert_eq!(SessionStatus::Unknown(code), SessionStatus::from(3));
+/// assert!(UnknownStatusCode::new(0).is_none());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde-derive",
+    derive(Serialize, Deserialize),
+    serde(transparent)
+)]
+pub struct UnknownStatusCode(
+    #[cfg_attr(feature = "serde-derive", serde(deserialize_with = "unknown_code"))] u8,
+);
+
+impl UnknownStatusCode {
+    /// Returns the code, or `None` if it has a named `SessionStatus`.
+    pub fn new(n: u8) -> Option<UnknownStatusCode> {
+        match SessionStatus::from(n) {
+            SessionStatus::Unknown(code) => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw status code.
+    pub fn code(self) -> u8 {
+        self.0
+    }
+}
+
+#[cfg(feature = "serde-derive")]
+fn unknown_code<'de, D>(deserializer: D) -> std::result::Result<u8, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de;
+    let n = u8::deserialize(deserializer)?;
+    match UnknownStatusCode::new(n) {
+        Some(code) => Ok(code.0),
+        None => Err(de::Error::custom("documented session status")),
+    }
 }
 
 impl SessionStatus {
     /// Creates a new session status from a code.
     ///
-    /// Returns an error if the code is unknown.
+    /// Returns an error if the code is unknown, use `SessionStatus::from` to keep unknown codes.
     ///
     /// # Examples
     ///
@@ -41,24 +95,17 @@ impl SessionStatus {
     /// use sbd_lib::mo::SessionStatus;
     /// assert!(SessionStatus::new(0).is_ok());
     /// assert!(SessionStatus::new(3).is_err());
+    /// assert_eq!(3, u8::from(SessionStatus::from(3)));
     /// ```
     pub fn new(n: u8) -> Result<SessionStatus> {
-        use crate::Error;
-        match n {
-            0 => Ok(SessionStatus::Ok),
-            1 => Ok(SessionStatus::OkMobileTerminatedTooLarge),
-            2 => Ok(SessionStatus::OkLocationUnacceptableQuality),
-            10 => Ok(SessionStatus::Timeout),
-            12 => Ok(SessionStatus::MobileOriginatedTooLarge),
-            13 => Ok(SessionStatus::RFLinkLoss),
-            14 => Ok(SessionStatus::IMEIProtocolAnomaly),
-            15 => Ok(SessionStatus::Prohibited),
-            _ => Err(Error::UnknownSessionStatus(n)),
+        match SessionStatus::from(n) {
+            SessionStatus::Unknown(_) => Err(Error::UnknownSessionStatus(n)),
+            status => Ok(status),
         }
     }
 
     pub fn value(&self) -> i8 {
-        *self as i8
+        u8::from(*self) as i8
     }
 
     pub fn is_ok(&self) -> bool {
@@ -71,6 +118,86 @@ impl SessionStatus {
             SessionStatus::RFLinkLoss => false,
             SessionStatus::IMEIProtocolAnomaly => false,
             SessionStatus::Prohibited => false,
+            SessionStatus::Unknown(_) => false,
         }
     }
 }
+
+impl From<u8> for SessionStatus {
+    fn from(n: u8) -> Self {
+        match n {
+            0 => SessionStatus::Ok,
+            1 => SessionStatus::OkMobileTerminatedTooLarge,
+            2 => SessionStatus::OkLocationUnacceptableQuality,
+            10 => SessionStatus::Timeout,
+            12 => SessionStatus::MobileOriginatedTooLarge,
+            13 => SessionStatus::RFLinkLoss,
+            14 => SessionStatus::IMEIProtocolAnomaly,
+            15 => SessionStatus::Prohibited,
+            n => SessionStatus::Unknown(UnknownStatusCode(n)),
+        }
+    }
+}
+
+impl From<SessionStatus> for u8 {
+    fn from(status: SessionStatus) -> Self {
+        match status {
+            SessionStatus::Ok => 0,
+            SessionStatus::OkMobileTerminatedTooLarge => 1,
+            SessionStatus::OkLocationUnacceptableQuality => 2,
+            SessionStatus::Timeout => 10,
+            SessionStatus::MobileOriginatedTooLarge => 12,
+            SessionStatus::RFLinkLoss => 13,
+            SessionStatus::IMEIProtocolAnomaly => 14,
+            SessionStatus::Prohibited => 15,
+            SessionStatus::Unknown(code) => code.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_code() {
+        for n in 0..=u8::MAX {
+            let status = SessionStatus::from(n);
+            assert_eq!(n, u8::from(status));
+            assert_eq!(
+                matches!(status, SessionStatus::Unknown(_)),
+                SessionStatus::new(n).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_only_holds_undocumented_codes() {
+        for n in 0..=u8::MAX {
+            let status = SessionStatus::from(n);
+            match UnknownStatusCode::new(n) {
+                Some(code) => assert_eq!(SessionStatus::Unknown(code), status),
+                None => assert_eq!(Ok(status), SessionStatus::new(n).map_err(|_| ())),
+            }
+        }
+    }
+
+    #[cfg(feature = "serde-derive")]
+    #[test]
+    fn deserialize_unknown_code() {
+        use serde::de::{value, Deserialize, IntoDeserializer};
+
+        let deserialize = |n: u8| -> std::result::Result<UnknownStatusCode, value::Error> {
+            UnknownStatusCode::deserialize(n.into_deserializer())
+        };
+        assert_eq!(UnknownStatusCode::new(3), deserialize(3).ok());
+        assert!(deserialize(0).is_err());
+    }
+
+    #[test]
+    fn unknown_is_not_ok() {
+        assert!(!SessionStatus::from(3).is_ok());
+        assert!(SessionStatus::from(2).is_ok());
+        assert!(!SessionStatus::from(10).is_ok());
+    }
+}
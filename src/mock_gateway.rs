@@ -0,0 +1,407 @@
+use crate::{mt, mt::MtStatus, DirectIpMessage, Imei, Message, Result};
+use std::{
+    io::Write,
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// How long the gateway waits on a silent client before dropping the connection, by default.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+struct State {
+    rejections: Vec<(Imei, MtStatus)>,
+    reject_all: Option<MtStatus>,
+    queue_limit: usize,
+    next_auto_id: u32,
+    queues: Vec<(Imei, usize)>,
+    received: Vec<Message>,
+    timeout: Duration,
+}
+
+impl State {
+    fn queue(&mut self, imei: Imei) -> &mut usize {
+        let index = match self.queues.iter().position(|(i, _)| *i == imei) {
+            Some(index) => index,
+            None => {
+                self.queues.push((imei, 0));
+                self.queues.len() - 1
+            }
+        };
+        &mut self.queues[index].1
+    }
+
+    fn confirm(&mut self, header: mt::Header, payload: &[u8]) -> mt::ConfirmationStatus {
+        let mut confirmation = mt::ConfirmationStatus {
+            message_id: header.message_id,
+            imei: header.imei,
+            auto_id: 0,
+            status: MtStatus::NoPayload,
+        };
+
+        let rejection = self
+            .rejections
+            .iter()
+            .find(|(imei, _)| *imei == header.imei)
+            .map(|(_, status)| *status);
+        if let Some(status) = self.reject_all.or(rejection) {
+            confirmation.status = status;
+            return confirmation;
+        }
+        if payload.len() > mt::MAX_PAYLOAD_LEN {
+            confirmation.status = MtStatus::PayloadTooLarge;
+            return confirmation;
+        }
+
+        let queue_limit = self.queue_limit;
+        let queue = self.queue(header.imei);
+        if header.flags.flush_mt_queue() {
+            *queue = 0;
+        }
+        if payload.is_empty() {
+            if header.flags.requires_payload() {
+                confirmation.status = MtStatus::PayloadExpected;
+                return confirmation;
+            }
+        } else if *queue >= queue_limit {
+            confirmation.status = MtStatus::QueueFull;
+            return confirmation;
+        } else {
+            *queue += 1;
+            confirmation.status = MtStatus::QueuePosition(*queue as u16);
+        }
+
+        confirmation.auto_id = self.next_auto_id;
+        self.next_auto_id = self.next_auto_id.wrapping_add(1);
+        confirmation
+    }
+}
+
+/// Configures and starts a `MockGateway`.
+#[derive(Debug)]
+pub struct MockGatewayBuilder {
+    state: State,
+}
+
+impl MockGatewayBuilder {
+    /// Rejects every message for `imei` with the given status.
+    pub fn reject_imei(mut self, imei: Imei, status: MtStatus) -> Self {
+        self.state.rejections.push((imei, status));
+        self
+    }
+
+    /// Rejects every message with the given status, e.g. `MtStatus::ResourcesUnavailable`.
+    pub fn reject_all(mut self, status: MtStatus) -> Self {
+        self.state.reject_all = Some(status);
+        self
+    }
+
+    /// Sets how many messages an IMEI can have queued before the queue is full (50 by default).
+    pub fn queue_limit(mut self, queue_limit: usize) -> Self {
+        self.state.queue_limit = queue_limit;
+        self
+    }
+
+    /// Sets the first `auto_id` assigned to accepted messages.
+    pub fn first_auto_id(mut self, auto_id: u32) -> Self {
+        self.state.next_auto_id = auto_id;
+        self
+    }
+
+    /// Sets how long a connection can stay silent before it is dropped (five seconds by default).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.state.timeout = timeout;
+        self
+    }
+
+    /// Starts the gateway on a free localhost port.
+    pub fn start(self) -> Result<MockGateway> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(self.state));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let state = Arc::clone(&state);
+            let shutdown = Arc::clone(&shutdown);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let _ = MockGateway::handle(stream, &state);
+                    }
+                }
+            })
+        };
+
+        Ok(MockGateway {
+            addr,
+            state,
+            shutdown,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// A stand-in for the Iridium MT gateway listening on localhost.
+///
+/// Every MT message is recorded and answered with a confirmation: accepted messages get an
+/// `auto_id` and their position in the IMEI's MT queue, and rejections follow the gateway's
+/// negative status codes. Connections are handled one at a time, and dropped when they stay silent
+/// longer than the builder's timeout. The gateway stops when dropped.
+///
+/// Only available with the `test-util` feature.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::client::MtClient;
+/// use sbd_lib::mock_gateway::MockGateway;
+/// use sbd_lib::mt::{self, MtStatus};
+/// use sbd_lib::Message;
+///
+/// let gateway = MockGateway::builder()
+///     .reject_imei("300434060009290".parse().unwrap(), MtStatus::UnknownImei)
+///     .start()
+///     .unwrap();
+/// let header = mt::Header {
+///     message_id: 1,
+///     imei: "300434060009290".parse().unwrap(),
+///     flags: mt::DispositionFlags::empty(),
+/// };
+/// let message = Message::new(header.into(), b"hello".to_vec(), None, vec![]);
+/// let confirmation = MtClient::new(gateway.addr()).unwrap().send(&message).unwrap();
+/// assert_eq!(MtStatus::UnknownImei, confirmation.status);
+/// assert_eq!(vec![message], gateway.received());
+/// ```
+#[derive(Debug)]
+pub struct MockGateway {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl MockGateway {
+    /// Returns a builder to script the gateway's responses.
+    pub fn builder() -> MockGatewayBuilder {
+        MockGatewayBuilder {
+            state: State {
+                rejections: Vec::new(),
+                reject_all: None,
+                queue_limit: 50,
+                next_auto_id: 1,
+                queues: Vec::new(),
+                received: Vec::new(),
+                timeout: DEFAULT_TIMEOUT,
+            },
+        }
+    }
+
+    /// Starts a gateway accepting every valid message.
+    pub fn start() -> Result<Self> {
+        Self::builder().start()
+    }
+
+    /// Returns the address the gateway is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns every MT message received so far.
+    pub fn received(&self) -> Vec<Message> {
+        self.state().received.clone()
+    }
+
+    /// Returns the number of messages queued for `imei`.
+    pub fn queue_len(&self, imei: Imei) -> usize {
+        self.state()
+            .queues
+            .iter()
+            .find(|(i, _)| *i == imei)
+            .map_or(0, |(_, len)| *len)
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn handle(mut stream: TcpStream, state: &Mutex<State>) -> Result<()> {
+        let timeout = state.lock().unwrap_or_else(|err| err.into_inner()).timeout;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        let message = match DirectIpMessage::read_from(&mut stream)? {
+            DirectIpMessage::MtMessage(message) => message,
+            _ => return Err(crate::Error::UnexpectedMessage),
+        };
+        let header = *message
+            .header()
+            .as_mt()
+            .ok_or(crate::Error::UnexpectedMessage)?;
+        let confirmation = {
+            let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+            let confirmation = state.confirm(header, message.payload());
+            state.received.push(message);
+            confirmation
+        };
+        DirectIpMessage::from(confirmation).write_to(&mut stream)?;
+        stream.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for MockGateway {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake up the accept loop so it notices the shutdown.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::MtClient;
+    use crate::mt::DispositionFlags;
+
+    fn imei() -> Imei {
+        (*b"300434060009290").into()
+    }
+
+    fn message(payload: &[u8]) -> Message {
+        message_to(imei(), DispositionFlags::empty(), payload)
+    }
+
+    fn message_to(imei: Imei, flags: DispositionFlags, payload: &[u8]) -> Message {
+        let header = mt::Header {
+            message_id: 7,
+            imei,
+            flags,
+        };
+        Message::new(header.into(), payload.to_vec(), None, vec![])
+    }
+
+    fn send(gateway: &MockGateway, message: &Message) -> mt::ConfirmationStatus {
+        MtClient::new(gateway.addr())
+            .unwrap()
+            .send(message)
+            .unwrap()
+    }
+
+    /// Sends a message without the checks `MtClient` makes, to get the gateway to reject it.
+    fn send_unchecked(gateway: &MockGateway, message: &Message) -> mt::ConfirmationStatus {
+        let mut stream = TcpStream::connect(gateway.addr()).unwrap();
+        message.write_to(&mut stream).unwrap();
+        match DirectIpMessage::read_from(stream).unwrap() {
+            DirectIpMessage::MtConfirmation(status) => status,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn queue_position() {
+        let gateway = MockGateway::builder().first_auto_id(100).start().unwrap();
+        let first = send(&gateway, &message(b"one"));
+        assert_eq!(
+            (7, 100, MtStatus::QueuePosition(1)),
+            (first.message_id, first.auto_id, first.status)
+        );
+        let second = send(&gateway, &message(b"two"));
+        assert_eq!(
+            (101, MtStatus::QueuePosition(2)),
+            (second.auto_id, second.status)
+        );
+        assert_eq!(2, gateway.queue_len(imei()));
+        assert_eq!(vec![message(b"one"), message(b"two")], gateway.received());
+    }
+
+    #[test]
+    fn queue_full() {
+        let gateway = MockGateway::builder().queue_limit(2).start().unwrap();
+        let flush = message_to(imei(), DispositionFlags::FLUSH_MT_QUEUE, b"");
+        assert_eq!(
+            MtStatus::QueuePosition(1),
+            send(&gateway, &message(b"a")).status
+        );
+        assert_eq!(
+            MtStatus::QueuePosition(2),
+            send(&gateway, &message(b"b")).status
+        );
+        assert_eq!(MtStatus::QueueFull, send(&gateway, &message(b"c")).status);
+        assert_eq!(MtStatus::NoPayload, send(&gateway, &flush).status);
+        assert_eq!(0, gateway.queue_len(imei()));
+        send(&gateway, &message(b"e"));
+        let flush = mt::MtMessage::builder()
+            .imei(imei())
+            .message_id(8)
+            .flags(DispositionFlags::FLUSH_MT_QUEUE)
+            .build()
+            .unwrap();
+        assert_eq!(MtStatus::NoPayload, send(&gateway, &flush.into()).status);
+        assert_eq!(0, gateway.queue_len(imei()));
+        assert_eq!(
+            MtStatus::QueuePosition(1),
+            send(&gateway, &message(b"d")).status
+        );
+    }
+
+    #[test]
+    fn rejections() {
+        let unknown: Imei = (*b"300434060009291").into();
+        let gateway = MockGateway::builder()
+            .reject_imei(unknown, MtStatus::UnknownImei)
+            .start()
+            .unwrap();
+        let confirmation = send(
+            &gateway,
+            &message_to(unknown, DispositionFlags::empty(), b"a"),
+        );
+        assert_eq!(
+            (0, MtStatus::UnknownImei),
+            (confirmation.auto_id, confirmation.status)
+        );
+        assert_eq!(
+            MtStatus::PayloadTooLarge,
+            send_unchecked(&gateway, &message(&[0; 1891])).status
+        );
+        assert_eq!(
+            MtStatus::PayloadExpected,
+            send(&gateway, &message(b"")).status
+        );
+        assert_eq!(3, gateway.received().len());
+
+        let gateway = MockGateway::builder()
+            .reject_all(MtStatus::ResourcesUnavailable)
+            .start()
+            .unwrap();
+        assert_eq!(
+            MtStatus::ResourcesUnavailable,
+            send(&gateway, &message(b"a")).status
+        );
+    }
+
+    #[test]
+    fn silent_client() {
+        let gateway = MockGateway::builder()
+            .timeout(Duration::from_millis(100))
+            .start()
+            .unwrap();
+        let _silent = TcpStream::connect(gateway.addr()).unwrap();
+        assert_eq!(
+            MtStatus::QueuePosition(1),
+            send(&gateway, &message(b"a")).status
+        );
+        let _silent = TcpStream::connect(gateway.addr()).unwrap();
+        drop(gateway);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::mt::MtStatus;
 use crate::{Imei, Result};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 #[cfg(feature = "serde-derive")]
@@ -17,11 +18,17 @@ pub struct ConfirmationStatus {
     /// The Iridium Gateway id for this message.
     pub auto_id: u32,
     /// Message Status
-    pub status: i16,
+    pub status: MtStatus,
 }
 impl ConfirmationStatus {
+    /// Returns true if the message was queued.
     pub fn status(&self) -> bool {
-        self.status > 0
+        self.status.queue_position().is_some()
+    }
+
+    /// Returns true if the gateway accepted the message.
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
     }
 
     pub fn read_from(read: &mut dyn Read) -> Result<Self> {
@@ -32,7 +39,7 @@ impl ConfirmationStatus {
         read.read_exact(&mut imei).map_err(Error::Io)?;
         let auto_id = read.read_u32::<BigEndian>().map_err(Error::Io)?;
 
-        let status = read.read_i16::<BigEndian>().map_err(Error::Io)?;
+        let status = read.read_i16::<BigEndian>().map_err(Error::Io)?.into();
 
         Ok(ConfirmationStatus {
             message_id,
@@ -48,10 +55,11 @@ impl ConfirmationStatus {
         write.write_u32::<BigEndian>(self.message_id)?;
         write.write_all(&self.imei)?;
         write.write_u32::<BigEndian>(self.auto_id)?;
-        write.write_i16::<BigEndian>(self.status)?;
+        write.write_i16::<BigEndian>(self.status.value())?;
         Ok(())
     }
 
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         28
     }
@@ -0,0 +1,216 @@
+use crate::{Error, Result};
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    fmt,
+    ops::{BitOr, BitOrAssign},
+};
+
+/// The mobile-terminated Disposition Flags.
+///
+/// The descriptions for these flags are taken directly from the `DirectIP` documentation.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::mt::DispositionFlags;
+/// let flags = DispositionFlags::FLUSH_MT_QUEUE | DispositionFlags::SEND_RING_ALERT;
+/// assert!(flags.send_ring_alert());
+/// assert!(!flags.high_priority());
+/// assert_eq!(0x0003, flags.bits());
+/// assert!(DispositionFlags::from_bits(0x0004).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde-derive",
+    derive(Serialize, Deserialize),
+    serde(transparent)
+)]
+pub struct DispositionFlags(u16);
+
+impl DispositionFlags {
+    /// Delete all MT payloads in the SSD's MT queue.
+    pub const FLUSH_MT_QUEUE: DispositionFlags = DispositionFlags(0x0001);
+    /// Send ring alert with no associated MT payload (normal ring alert rules apply).
+    pub const SEND_RING_ALERT: DispositionFlags = DispositionFlags(0x0002);
+    /// Update SSD location with given lat/lon values.
+    pub const UPDATE_SSD_LOCATION: DispositionFlags = DispositionFlags(0x0008);
+    /// Place the associated MT payload in queue based on priority level.
+    pub const HIGH_PRIORITY: DispositionFlags = DispositionFlags(0x0010);
+    /// Use the value in the Unique ID field as the MTMSN.
+    pub const ASSIGN_MTMSN: DispositionFlags = DispositionFlags(0x0020);
+
+    const KNOWN: u16 = 0x0001 | 0x0002 | 0x0008 | 0x0010 | 0x0020;
+
+    const NAMES: [(DispositionFlags, &'static str); 5] = [
+        (Self::FLUSH_MT_QUEUE, "flush MT queue"),
+        (Self::SEND_RING_ALERT, "send ring alert"),
+        (Self::UPDATE_SSD_LOCATION, "update SSD location"),
+        (Self::HIGH_PRIORITY, "high priority"),
+        (Self::ASSIGN_MTMSN, "assign MTMSN"),
+    ];
+
+    /// Returns flags with nothing set.
+    pub const fn empty() -> Self {
+        DispositionFlags(0)
+    }
+
+    /// Creates flags from their wire value.
+    ///
+    /// Returns an error if any reserved bit is set.
+    pub fn from_bits(bits: u16) -> Result<Self> {
+        if bits & !Self::KNOWN == 0 {
+            Ok(DispositionFlags(bits))
+        } else {
+            Err(Error::ReservedDispositionFlags(bits))
+        }
+    }
+
+    /// Creates flags from their wire value, keeping any reserved bits.
+    pub const fn from_bits_retain(bits: u16) -> Self {
+        DispositionFlags(bits)
+    }
+
+    /// Returns the wire value of these flags.
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the reserved bits that are set.
+    pub const fn reserved(self) -> u16 {
+        self.0 & !Self::KNOWN
+    }
+
+    /// Returns true if no flag is set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if all flags of `other` are set.
+    pub const fn contains(self, other: DispositionFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if the MT queue should be flushed.
+    pub const fn flush_mt_queue(self) -> bool {
+        self.contains(Self::FLUSH_MT_QUEUE)
+    }
+
+    /// Returns true if a ring alert without MT payload should be sent.
+    pub const fn send_ring_alert(self) -> bool {
+        self.contains(Self::SEND_RING_ALERT)
+    }
+
+    /// Returns true if the SSD location should be updated.
+    pub const fn update_ssd_location(self) -> bool {
+        self.contains(Self::UPDATE_SSD_LOCATION)
+    }
+
+    /// Returns true if the message is high priority.
+    pub const fn high_priority(self) -> bool {
+        self.contains(Self::HIGH_PRIORITY)
+    }
+
+    /// Returns true if the message id should be used as MTMSN.
+    pub const fn assign_mtmsn(self) -> bool {
+        self.contains(Self::ASSIGN_MTMSN)
+    }
+
+    /// Returns true if a message with these flags needs a payload.
+    ///
+    /// Flushing the MT queue or sending a ring alert is meaningful without one.
+    pub const fn requires_payload(self) -> bool {
+        !self.flush_mt_queue() && !self.send_ring_alert()
+    }
+}
+
+impl BitOr for DispositionFlags {
+    type Output = DispositionFlags;
+
+    fn bitor(self, rhs: DispositionFlags) -> DispositionFlags {
+        DispositionFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for DispositionFlags {
+    fn bitor_assign(&mut self, rhs: DispositionFlags) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl TryFrom<u16> for DispositionFlags {
+    type Error = Error;
+
+    fn try_from(bits: u16) -> Result<Self> {
+        Self::from_bits(bits)
+    }
+}
+
+impl From<DispositionFlags> for u16 {
+    fn from(flags: DispositionFlags) -> Self {
+        flags.bits()
+    }
+}
+
+impl fmt::Display for DispositionFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = Self::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name);
+        match names.next() {
+            Some(name) => write!(f, "{}", name)?,
+            None if self.reserved() == 0 => return write!(f, "none"),
+            None => return write!(f, "reserved {:#06x}", self.reserved()),
+        }
+        for name in names {
+            write!(f, ", {}", name)?;
+        }
+        if self.reserved() != 0 {
+            write!(f, ", reserved {:#06x}", self.reserved())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_bits() {
+        for bits in 0..=u16::MAX {
+            let flags = DispositionFlags::from_bits_retain(bits);
+            assert_eq!(bits, u16::from(flags));
+            assert_eq!(
+                bits & 0xffc4 == 0,
+                DispositionFlags::from_bits(bits).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn predicates() {
+        let flags = DispositionFlags::UPDATE_SSD_LOCATION | DispositionFlags::ASSIGN_MTMSN;
+        assert_eq!(0x0028, flags.bits());
+        assert!(!flags.flush_mt_queue());
+        assert!(!flags.send_ring_alert());
+        assert!(flags.update_ssd_location());
+        assert!(!flags.high_priority());
+        assert!(flags.assign_mtmsn());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("none", DispositionFlags::empty().to_string());
+        assert_eq!(
+            "flush MT queue, high priority",
+            (DispositionFlags::FLUSH_MT_QUEUE | DispositionFlags::HIGH_PRIORITY).to_string()
+        );
+        assert_eq!(
+            "send ring alert, reserved 0x0100",
+            DispositionFlags::from_bits_retain(0x0102).to_string()
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::information_element::SbdHeader;
-use crate::{Imei, Result};
+use crate::mt::DispositionFlags;
+use crate::{Imei, ParseOptions, Result};
 #[cfg(feature = "serde-derive")]
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
@@ -13,7 +14,7 @@ pub struct Header {
     /// The device id.
     pub imei: Imei,
     /// The mobile-terminated Disposition Flags
-    pub flags: u16,
+    pub flags: DispositionFlags,
 }
 
 impl SbdHeader for Header {
@@ -24,31 +25,43 @@ impl SbdHeader for Header {
         write.write_u16::<BigEndian>(21)?;
         write.write_u32::<BigEndian>(self.message_id)?;
         write.write_all(&self.imei)?;
-        write.write_u16::<BigEndian>(self.flags)?;
+        write.write_u16::<BigEndian>(self.flags.bits())?;
         Ok(())
     }
 
-    fn imei(&self) -> &str {
-        use std::str;
-        str::from_utf8(&self.imei).expect("IMEI numbers are specified to be ascii number")
+    fn imei(&self) -> &Imei {
+        &self.imei
     }
     fn len(&self) -> usize {
         24
     }
     fn as_mt(&self) -> Option<&Header> {
-        Some(&self)
+        Some(self)
     }
 }
 
 impl Header {
     pub fn read_from(read: &mut dyn Read) -> Result<Header> {
+        Self::read_from_with(read, ParseOptions::default())
+    }
+
+    /// Reads the header with the given options, following the IEI and length.
+    ///
+    /// Reserved disposition flags are kept, unless parsing is strict.
+    pub fn read_from_with(read: &mut dyn Read, options: ParseOptions) -> Result<Header> {
         use crate::Error;
         use byteorder::{BigEndian, ReadBytesExt};
         let message_id = read.read_u32::<BigEndian>().map_err(Error::Io)?;
         let mut imei = [0; 15];
         read.read_exact(&mut imei).map_err(Error::Io)?;
+        options.check_imei(imei.into())?;
 
-        let flags = read.read_u16::<BigEndian>().map_err(Error::Io)?;
+        let bits = read.read_u16::<BigEndian>().map_err(Error::Io)?;
+        let flags = if options.is_strict() {
+            DispositionFlags::from_bits(bits)?
+        } else {
+            DispositionFlags::from_bits_retain(bits)
+        };
 
         Ok(Header {
             message_id,
@@ -0,0 +1,370 @@
+use crate::mt::{DispositionFlags, Header, MtPriority};
+use crate::{Error, Imei, InformationElement, Message, Result};
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, io::Write};
+
+/// The largest MT payload the gateway accepts.
+pub const MAX_PAYLOAD_LEN: usize = 1890;
+
+/// A mobile-terminated message, sent to the gateway for delivery to a device.
+///
+/// Unlike `Message`, it can only hold what belongs to a mobile-terminated message. The payload
+/// can only be left out when the disposition flags flush the MT queue or send a ring alert.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::mt::{DispositionFlags, MtMessage, MtPriority};
+/// let message = MtMessage::builder()
+///     .imei("300234063904190".parse().unwrap())
+///     .message_id(1)
+///     .flags(DispositionFlags::HIGH_PRIORITY)
+///     .priority(MtPriority::HIGHEST)
+///     .payload(&b"reboot"[..])
+///     .build()
+///     .unwrap();
+/// let mut buff = vec![];
+/// message.write_to(&mut buff).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+pub struct MtMessage {
+    header: Header,
+    payload: Option<Vec<u8>>,
+    priority: Option<MtPriority>,
+    information_elements: Vec<InformationElement>,
+}
+
+impl MtMessage {
+    /// Returns a builder for a new message.
+    pub fn builder() -> MtMessageBuilder {
+        MtMessageBuilder::default()
+    }
+
+    /// Returns this message's header.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns this message's Unique Client Message ID.
+    pub fn message_id(&self) -> u32 {
+        self.header.message_id
+    }
+
+    /// Returns this message's imei.
+    pub fn imei(&self) -> &Imei {
+        &self.header.imei
+    }
+
+    /// Returns this message's disposition flags.
+    pub fn flags(&self) -> DispositionFlags {
+        self.header.flags
+    }
+
+    /// Returns this message's payload, if it has one.
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.payload.as_deref()
+    }
+
+    /// Returns this message's priority.
+    pub fn priority(&self) -> Option<MtPriority> {
+        self.priority
+    }
+
+    /// Returns the information elements this library does not know.
+    pub fn information_elements(&self) -> &[InformationElement] {
+        &self.information_elements
+    }
+
+    /// Writes this message to a `Write`.
+    pub fn write_to<W: Write>(&self, write: W) -> Result<()> {
+        Message::from(self.clone()).write_to(write)
+    }
+}
+
+impl From<MtMessage> for Message {
+    fn from(message: MtMessage) -> Self {
+        Message::from_parts(
+            message.header.into(),
+            message.payload,
+            None,
+            message.priority,
+            message.information_elements,
+            Vec::new(),
+        )
+    }
+}
+
+impl TryFrom<Message> for MtMessage {
+    type Error = Error;
+
+    /// Converts a message with a mobile-terminated header.
+    ///
+    /// The message is checked like `MtMessageBuilder::build` does, and must not hold any
+    /// information element of mobile-originated messages.
+    fn try_from(message: Message) -> Result<Self> {
+        let (header, payload, location, priority, information_elements) = message.into_parts();
+        let header = match header {
+            crate::Header::MTHeader(header) => header,
+            crate::Header::MOHeader(_) => return Err(Error::UnexpectedMessage),
+        };
+        if location.is_some() {
+            return Err(Error::DirectionMismatch {
+                iei: 0x03,
+                mobile_originated: false,
+            });
+        }
+        crate::message_ref::check_directions(
+            &header.into(),
+            information_elements.iter().map(InformationElement::iei),
+        )
+        .map_err(|(_, err)| err)?;
+        validate(&header, payload.as_deref())?;
+        Ok(MtMessage {
+            header,
+            payload,
+            priority,
+            information_elements,
+        })
+    }
+}
+
+/// Builds a `MtMessage`, checking it can be sent to the gateway.
+#[derive(Clone, Debug, Default)]
+pub struct MtMessageBuilder {
+    message_id: Option<u32>,
+    imei: Option<Imei>,
+    flags: DispositionFlags,
+    priority: Option<MtPriority>,
+    payload: Option<Vec<u8>>,
+}
+
+impl MtMessageBuilder {
+    /// Sets the Unique Client Message ID, which is required.
+    pub fn message_id(mut self, message_id: u32) -> Self {
+        self.message_id = Some(message_id);
+        self
+    }
+
+    /// Sets the device id, which is required.
+    pub fn imei(mut self, imei: Imei) -> Self {
+        self.imei = Some(imei);
+        self
+    }
+
+    /// Sets the disposition flags, none by default.
+    pub fn flags(mut self, flags: DispositionFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Sets the priority of the message in the MT queue.
+    pub fn priority(mut self, priority: MtPriority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Sets the payload, which is required unless the disposition flags flush the MT queue or
+    /// send a ring alert.
+    pub fn payload<P: Into<Vec<u8>>>(mut self, payload: P) -> Self {
+        self.payload = Some(payload.into());
+        self
+    }
+
+    /// Builds the message.
+    ///
+    /// Returns an error if a required field is missing, the IMEI is not numeric, reserved
+    /// disposition flags are set or the payload is larger than the gateway accepts.
+    pub fn build(self) -> Result<MtMessage> {
+        let message_id = self.message_id.ok_or(Error::MissingField("message_id"))?;
+        let imei = self.imei.ok_or(Error::MissingField("imei"))?;
+        let header = Header {
+            message_id,
+            imei,
+            flags: self.flags,
+        };
+        validate(&header, self.payload.as_deref())?;
+        Ok(MtMessage {
+            header,
+            payload: self.payload,
+            priority: self.priority,
+            information_elements: Vec::new(),
+        })
+    }
+}
+
+/// Checks the IMEI is numeric, no reserved disposition flags are set and the payload is present
+/// when required and not larger than the gateway accepts.
+fn validate(header: &Header, payload: Option<&[u8]>) -> Result<()> {
+    if !header.imei.is_numeric() {
+        return Err(Error::InvalidImei(header.imei));
+    }
+    if header.flags.reserved() != 0 {
+        return Err(Error::ReservedDispositionFlags(header.flags.bits()));
+    }
+    match payload {
+        None if header.flags.requires_payload() => Err(Error::NoPayload),
+        Some(payload) if payload.len() > MAX_PAYLOAD_LEN => Err(Error::PayloadTooLarge {
+            length: payload.len(),
+            max: MAX_PAYLOAD_LEN,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> MtMessageBuilder {
+        MtMessage::builder()
+            .imei((*b"300234063904190").into())
+            .message_id(7)
+            .payload(&b"reboot"[..])
+    }
+
+    #[test]
+    fn build() {
+        let message = builder()
+            .flags(DispositionFlags::FLUSH_MT_QUEUE)
+            .priority(MtPriority::LOWEST)
+            .build()
+            .unwrap();
+        assert_eq!(7, message.message_id());
+        assert_eq!("300234063904190", message.imei());
+        assert_eq!(DispositionFlags::FLUSH_MT_QUEUE, message.flags());
+        assert_eq!(Some(MtPriority::LOWEST), message.priority());
+
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+        let read_back = Message::read_from(buff.as_slice()).unwrap();
+        assert_eq!(b"reboot", read_back.payload());
+        assert_eq!(message, MtMessage::try_from(read_back).unwrap());
+    }
+
+    #[test]
+    fn required_fields() {
+        let missing = |builder: MtMessageBuilder| match builder.build() {
+            Err(Error::MissingField(field)) => field,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!("imei", missing(MtMessage::builder().message_id(1)));
+        assert_eq!(
+            "message_id",
+            missing(MtMessage::builder().imei((*b"300234063904190").into()))
+        );
+        assert!(matches!(
+            builder().payload(Vec::new()).build(),
+            Ok(message) if message.payload() == Some(&[][..])
+        ));
+        let mut no_payload = builder();
+        no_payload.payload = None;
+        assert!(matches!(no_payload.build(), Err(Error::NoPayload)));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            builder().imei((*b"30023406390419x").into()).build(),
+            Err(Error::InvalidImei(_))
+        ));
+        assert!(matches!(
+            builder()
+                .flags(DispositionFlags::from_bits_retain(0x0004))
+                .build(),
+            Err(Error::ReservedDispositionFlags(0x0004))
+        ));
+        assert!(builder().payload(vec![0; MAX_PAYLOAD_LEN]).build().is_ok());
+        assert!(matches!(
+            builder().payload(vec![0; MAX_PAYLOAD_LEN + 1]).build(),
+            Err(Error::PayloadTooLarge {
+                length: 1891,
+                max: 1890
+            })
+        ));
+    }
+
+    #[test]
+    fn try_from_checks() {
+        let header = |flags| Header {
+            message_id: 7,
+            imei: (*b"300234063904190").into(),
+            flags,
+        };
+        let with = |header: Header, payload: Vec<u8>, information_elements| {
+            MtMessage::try_from(Message::new(
+                header.into(),
+                payload,
+                None,
+                information_elements,
+            ))
+        };
+        assert!(matches!(
+            with(
+                header(DispositionFlags::empty()),
+                vec![0; MAX_PAYLOAD_LEN + 1],
+                vec![]
+            ),
+            Err(Error::PayloadTooLarge { .. })
+        ));
+        assert!(matches!(
+            with(
+                header(DispositionFlags::from_bits_retain(0x8000)),
+                b"reboot".to_vec(),
+                vec![]
+            ),
+            Err(Error::ReservedDispositionFlags(0x8000))
+        ));
+        assert!(matches!(
+            with(
+                header(DispositionFlags::empty()),
+                b"reboot".to_vec(),
+                vec![crate::mo::ConfirmationStatus::new(true).into()]
+            ),
+            Err(Error::DirectionMismatch {
+                iei: 0x05,
+                mobile_originated: false
+            })
+        ));
+        assert!(with(
+            header(DispositionFlags::empty()),
+            b"reboot".to_vec(),
+            vec![]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn header_only() {
+        for flags in [
+            DispositionFlags::FLUSH_MT_QUEUE,
+            DispositionFlags::SEND_RING_ALERT,
+        ] {
+            let message = MtMessage::builder()
+                .imei((*b"300234063904190").into())
+                .message_id(7)
+                .flags(flags)
+                .build()
+                .unwrap();
+            assert_eq!(None, message.payload());
+
+            let mut buff = vec![];
+            message.write_to(&mut buff).unwrap();
+            assert_eq!(&[1, 0, 24, 0x41, 0, 21], &buff[..6]);
+            assert_eq!(27, buff.len());
+            let read_back = Message::read_from(buff.as_slice()).unwrap();
+            assert!(!read_back.has_payload());
+            assert_eq!(message, MtMessage::try_from(read_back).unwrap());
+        }
+    }
+
+    #[test]
+    fn from_mo_message() {
+        let message = Message::from_path("data/0-mo.sbd").unwrap();
+        assert!(matches!(
+            MtMessage::try_from(message),
+            Err(Error::UnexpectedMessage)
+        ));
+    }
+}
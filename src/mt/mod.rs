@@ -1,5 +1,13 @@
-mod header;
 mod confirmation_status;
+mod disposition_flags;
+mod header;
+mod message;
+mod priority;
+mod status;
 
-pub use self::header::Header;
 pub use self::confirmation_status::ConfirmationStatus;
+pub use self::disposition_flags::DispositionFlags;
+pub use self::header::Header;
+pub use self::message::{MtMessage, MtMessageBuilder, MAX_PAYLOAD_LEN};
+pub use self::priority::MtPriority;
+pub use self::status::MtStatus;
@@ -0,0 +1,122 @@
+use crate::{Error, Result};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    fmt,
+    io::{Read, Write},
+};
+
+/// The priority of a mobile-terminated message.
+///
+/// Messages with a higher priority are placed ahead of lower priority ones in the MT queue. Level
+/// 1 is the highest priority, level 5 the lowest.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::mt::MtPriority;
+/// let priority = MtPriority::new(2).unwrap();
+/// assert_eq!(2, priority.level());
+/// assert_eq!(1, MtPriority::HIGHEST.level());
+/// assert!(MtPriority::new(6).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde-derive",
+    derive(Serialize, Deserialize),
+    serde(try_from = "u16", into = "u16")
+)]
+pub struct MtPriority(u16);
+
+impl MtPriority {
+    /// The highest priority level.
+    pub const HIGHEST: MtPriority = MtPriority(1);
+    /// The lowest priority level.
+    pub const LOWEST: MtPriority = MtPriority(5);
+
+    /// Creates a priority from its level, between 1 (highest) and 5 (lowest).
+    pub fn new(level: u16) -> Result<Self> {
+        if (Self::HIGHEST.0..=Self::LOWEST.0).contains(&level) {
+            Ok(MtPriority(level))
+        } else {
+            Err(Error::InvalidPriority(level))
+        }
+    }
+
+    /// Returns the priority level, 1 being the highest priority.
+    pub const fn level(self) -> u16 {
+        self.0
+    }
+
+    /// Reads the priority level, following the IEI and length.
+    pub fn read_from(read: &mut dyn Read) -> Result<Self> {
+        Self::new(read.read_u16::<BigEndian>()?)
+    }
+
+    /// Writes the whole priority information element, IEI and length included.
+    pub fn write_to<W: Write>(&self, write: &mut W) -> Result<()> {
+        write.write_u8(0x46)?;
+        write.write_u16::<BigEndian>(2)?;
+        write.write_u16::<BigEndian>(self.0)?;
+        Ok(())
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        5
+    }
+}
+
+impl TryFrom<u16> for MtPriority {
+    type Error = Error;
+
+    fn try_from(level: u16) -> Result<Self> {
+        Self::new(level)
+    }
+}
+
+impl From<MtPriority> for u16 {
+    fn from(priority: MtPriority) -> Self {
+        priority.level()
+    }
+}
+
+impl fmt::Display for MtPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels() {
+        for level in 0..=u16::MAX {
+            assert_eq!(
+                (1..=5).contains(&level),
+                MtPriority::new(level).is_ok(),
+                "{}",
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut buff = vec![];
+        MtPriority::new(3).unwrap().write_to(&mut buff).unwrap();
+        assert_eq!(vec![0x46, 0, 2, 0, 3], buff);
+        assert_eq!(
+            MtPriority::new(3).unwrap(),
+            MtPriority::read_from(&mut &buff[3..]).unwrap()
+        );
+        assert!(matches!(
+            MtPriority::read_from(&mut &[0u8, 0][..]),
+            Err(Error::InvalidPriority(0))
+        ));
+    }
+}
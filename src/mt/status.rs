@@ -0,0 +1,209 @@
+use crate::{Error, Result};
+#[cfg(feature = "serde-derive")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The status of a mobile-terminated message, as reported in its confirmation.
+///
+/// The descriptions for these codes are taken directly from the `DirectIP` documentation.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::mt::MtStatus;
+/// assert_eq!(MtStatus::QueuePosition(3), MtStatus::from(3));
+/// assert_eq!(MtStatus::QueueFull, MtStatus::from(-5));
+/// assert_eq!(-5, i16::from(MtStatus::QueueFull));
+/// assert!(!MtStatus::QueueFull.is_success());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
+pub enum MtStatus {
+    /// Successful, the value is the order of the message in the MT message queue.
+    ///
+    /// Only positions up to `i16::MAX` can be represented on the wire, see `MtStatus::queued`.
+    QueuePosition(u16),
+    /// Successful, no payload in message.
+    NoPayload,
+    /// Invalid IMEI – too few characters, non-numeric characters.
+    InvalidImei,
+    /// Unknown IMEI – not provisioned on the GSS.
+    UnknownImei,
+    /// Payload size exceeded maximum allowed.
+    PayloadTooLarge,
+    /// Payload expected, but none received.
+    PayloadExpected,
+    /// MT message queue full (max of 50).
+    QueueFull,
+    /// MT resources unavailable.
+    ResourcesUnavailable,
+    /// Violation of MT DirectIP protocol error.
+    ProtocolViolation,
+    /// Ring alerts to the given IMEI are disabled.
+    RingAlertsDisabled,
+    /// The given IMEI is not attached (not set to receive ring alerts).
+    ImeiNotAttached,
+    /// Source IP address rejected by MT filter.
+    SourceIpRejected,
+    /// MTMSN value is out of range (valid range is 1 – 65,535).
+    MtmsnOutOfRange,
+    /// A status code not documented by `DirectIP`.
+    Unknown(i16),
+}
+
+impl MtStatus {
+    /// Creates a successful status for the given position in the MT queue.
+    ///
+    /// Returns an error if the position is 0 or above `i16::MAX`, which cannot be sent on the
+    /// wire.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sbd_lib::mt::MtStatus;
+    /// assert_eq!(MtStatus::QueuePosition(3), MtStatus::queued(3).unwrap());
+    /// assert!(MtStatus::queued(40000).is_err());
+    /// ```
+    pub fn queued(position: u16) -> Result<Self> {
+        if position == 0 || position > i16::MAX as u16 {
+            Err(Error::InvalidQueuePosition(position))
+        } else {
+            Ok(MtStatus::QueuePosition(position))
+        }
+    }
+
+    /// Returns the wire value of this status.
+    ///
+    /// Queue positions above `i16::MAX` are clamped to it.
+    pub fn value(&self) -> i16 {
+        match *self {
+            MtStatus::QueuePosition(position) => position.min(i16::MAX as u16) as i16,
+            MtStatus::NoPayload => 0,
+            MtStatus::InvalidImei => -1,
+            MtStatus::UnknownImei => -2,
+            MtStatus::PayloadTooLarge => -3,
+            MtStatus::PayloadExpected => -4,
+            MtStatus::QueueFull => -5,
+            MtStatus::ResourcesUnavailable => -6,
+            MtStatus::ProtocolViolation => -7,
+            MtStatus::RingAlertsDisabled => -8,
+            MtStatus::ImeiNotAttached => -9,
+            MtStatus::SourceIpRejected => -10,
+            MtStatus::MtmsnOutOfRange => -11,
+            MtStatus::Unknown(value) => value,
+        }
+    }
+
+    /// Returns true if the gateway accepted the message.
+    pub fn is_success(&self) -> bool {
+        matches!(self, MtStatus::QueuePosition(_) | MtStatus::NoPayload)
+    }
+
+    /// Returns the position of the message in the MT queue, if it was queued.
+    pub fn queue_position(&self) -> Option<u16> {
+        match *self {
+            MtStatus::QueuePosition(position) => Some(position),
+            _ => None,
+        }
+    }
+}
+
+impl From<i16> for MtStatus {
+    fn from(value: i16) -> Self {
+        match value {
+            0 => MtStatus::NoPayload,
+            -1 => MtStatus::InvalidImei,
+            -2 => MtStatus::UnknownImei,
+            -3 => MtStatus::PayloadTooLarge,
+            -4 => MtStatus::PayloadExpected,
+            -5 => MtStatus::QueueFull,
+            -6 => MtStatus::ResourcesUnavailable,
+            -7 => MtStatus::ProtocolViolation,
+            -8 => MtStatus::RingAlertsDisabled,
+            -9 => MtStatus::ImeiNotAttached,
+            -10 => MtStatus::SourceIpRejected,
+            -11 => MtStatus::MtmsnOutOfRange,
+            value if value > 0 => MtStatus::QueuePosition(value as u16),
+            value => MtStatus::Unknown(value),
+        }
+    }
+}
+
+impl From<MtStatus> for i16 {
+    fn from(status: MtStatus) -> Self {
+        status.value()
+    }
+}
+
+impl fmt::Display for MtStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MtStatus::QueuePosition(position) => {
+                write!(f, "successful, position {} in the MT queue", position)
+            }
+            MtStatus::NoPayload => write!(f, "successful, no payload in message"),
+            MtStatus::InvalidImei => {
+                write!(
+                    f,
+                    "invalid IMEI, too few characters or non-numeric characters"
+                )
+            }
+            MtStatus::UnknownImei => write!(f, "unknown IMEI, not provisioned on the GSS"),
+            MtStatus::PayloadTooLarge => write!(f, "payload size exceeded maximum allowed"),
+            MtStatus::PayloadExpected => write!(f, "payload expected, but none received"),
+            MtStatus::QueueFull => write!(f, "MT message queue full"),
+            MtStatus::ResourcesUnavailable => write!(f, "MT resources unavailable"),
+            MtStatus::ProtocolViolation => write!(f, "violation of MT DirectIP protocol"),
+            MtStatus::RingAlertsDisabled => {
+                write!(f, "ring alerts to the given IMEI are disabled")
+            }
+            MtStatus::ImeiNotAttached => write!(
+                f,
+                "the given IMEI is not attached (not set to receive ring alerts)"
+            ),
+            MtStatus::SourceIpRejected => write!(f, "source IP address rejected by MT filter"),
+            MtStatus::MtmsnOutOfRange => write!(f, "MTMSN value is out of range"),
+            MtStatus::Unknown(value) => write!(f, "unknown status {}", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_value() {
+        for value in i16::MIN..=i16::MAX {
+            assert_eq!(value, MtStatus::from(value).value());
+        }
+    }
+
+    #[test]
+    fn queue_position_out_of_range() {
+        assert_eq!(i16::MAX, MtStatus::QueuePosition(40000).value());
+        assert!(matches!(
+            MtStatus::queued(40000),
+            Err(Error::InvalidQueuePosition(40000))
+        ));
+        assert!(matches!(
+            MtStatus::queued(0),
+            Err(Error::InvalidQueuePosition(0))
+        ));
+        assert_eq!(i16::MAX, MtStatus::queued(i16::MAX as u16).unwrap().value());
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(MtStatus::Unknown(-12), MtStatus::from(-12));
+        assert_eq!("unknown status -12", MtStatus::from(-12).to_string());
+        assert!(!MtStatus::Unknown(-12).is_success());
+    }
+
+    #[test]
+    fn success() {
+        assert!(MtStatus::NoPayload.is_success());
+        assert_eq!(None, MtStatus::NoPayload.queue_position());
+        assert_eq!(Some(50), MtStatus::from(50).queue_position());
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{Error, Imei, Result};
+
+/// Controls how strictly incoming messages are checked while parsing.
+///
+/// The default is lenient: anything that can be represented is accepted and kept as received,
+/// so it can be written back unchanged. Strict parsing rejects everything the `DirectIP`
+/// documentation does not allow.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::{Message, ParseOptions};
+/// let file = std::fs::File::open("data/1-mo-location.sbd").unwrap();
+/// let message = Message::read_from_with(file, ParseOptions::strict()).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    strict: bool,
+    verify_check_digit: bool,
+    skip_imei_check: bool,
+}
+
+impl ParseOptions {
+    /// Returns options accepting anything that can be represented.
+    pub const fn lenient() -> Self {
+        ParseOptions {
+            strict: false,
+            verify_check_digit: false,
+            skip_imei_check: false,
+        }
+    }
+
+    /// Returns options rejecting anything the `DirectIP` documentation does not allow.
+    pub const fn strict() -> Self {
+        ParseOptions {
+            strict: true,
+            verify_check_digit: false,
+            skip_imei_check: false,
+        }
+    }
+
+    /// Returns true if parsing is strict.
+    pub const fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Sets whether header IMEIs must end with their Luhn check digit.
+    ///
+    /// This is off even for strict parsing: Iridium gateways usually send `0` instead of the
+    /// check digit.
+    pub const fn verify_check_digit(mut self, verify_check_digit: bool) -> Self {
+        self.verify_check_digit = verify_check_digit;
+        self
+    }
+
+    /// Returns true if header IMEIs must end with their Luhn check digit.
+    pub const fn verifies_check_digit(&self) -> bool {
+        self.verify_check_digit
+    }
+
+    /// Accepts any header IMEI, so validation can report it on its own and still read the rest
+    /// of the header.
+    pub(crate) const fn skip_imei_check(mut self) -> Self {
+        self.skip_imei_check = true;
+        self
+    }
+
+    /// Checks a header IMEI according to these options.
+    ///
+    /// IMEIs must always be numeric, unless the check is skipped.
+    pub(crate) fn check_imei(&self, imei: Imei) -> Result<()> {
+        if self.skip_imei_check {
+            Ok(())
+        } else if self.verify_check_digit {
+            imei.validate()
+        } else if !imei.is_numeric() {
+            Err(Error::InvalidImei(imei))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
-    information_element::{Header, InformationElement, SbdHeader},
+    information_element::{Header, InformationElement, ParsedFrame, SbdHeader},
     mo::LocationInformation,
-    Result,
+    mt, Imei, ParseOptions, Result,
 };
 #[cfg(feature = "serde-derive")]
 use serde::{Deserialize, Serialize};
@@ -10,26 +10,75 @@ use std::{
     io::{Read, Write},
     path::Path,
 };
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 
 const PROTOCOL_REVISION_NUMBER: u8 = 1;
 
-/// A Iridium SBD message.
+/// Where an information element of a message is stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum IeSlot {
+    Header,
+    Payload,
+    Location,
+    Priority,
+    /// An index into the other information elements.
+    Other(usize),
+}
+
+impl IeSlot {
+    /// The order `Message::write_to` writes information elements in, before the other ones.
+    pub(crate) const CANONICAL: [IeSlot; 4] = [
+        IeSlot::Header,
+        IeSlot::Payload,
+        IeSlot::Location,
+        IeSlot::Priority,
+    ];
+}
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+/// A Iridium SBD message.
+///
+/// Messages that are read remember the order of their information elements, so they can be
+/// written back in the order they were received with `write_to_original_order`. That order is not
+/// taken into account when comparing messages.
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde-derive", derive(Serialize, Deserialize))]
 pub struct Message {
     header: Header,
-    payload: Vec<u8>,
+    payload: Option<Vec<u8>>,
     location: Option<LocationInformation>,
+    #[cfg_attr(feature = "serde-derive", serde(default))]
+    priority: Option<mt::MtPriority>,
     information_elements: Vec<InformationElement>,
+    #[cfg_attr(feature = "serde-derive", serde(skip))]
+    order: Vec<IeSlot>,
+    /// Bytes lenient parsing skipped past the length an information element requires, by
+    /// position in `order`.
+    #[cfg_attr(feature = "serde-derive", serde(skip))]
+    skipped: Vec<(usize, Vec<u8>)>,
+}
+
+impl PartialEq for Message {
+    fn eq(&self, other: &Self) -> bool {
+        self.header == other.header
+            && self.payload == other.payload
+            && self.location == other.location
+            && self.priority == other.priority
+            && self.information_elements == other.information_elements
+    }
 }
 
+impl Eq for Message {}
+
 impl fmt::Display for Message {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Header: {}, payload: {:?}", self.header, self.payload)?;
+        write!(f, "Header: {}, payload: {:?}", self.header, self.payload())?;
         if let Some(location) = self.location {
             write!(f, ", location: {}", location)?;
         }
+        if let Some(priority) = self.priority {
+            write!(f, ", priority: {}", priority)?;
+        }
         if !self.information_elements.is_empty() {
             write!(f, ",ie {:?}", self.information_elements)?;
         }
@@ -58,7 +107,22 @@ impl Message {
     /// let payload = message.payload();
     /// ```
     pub fn payload(&self) -> &[u8] {
-        &self.payload
+        self.payload.as_deref().unwrap_or_default()
+    }
+
+    /// Returns true if this message has a payload information element, possibly empty.
+    ///
+    /// Mobile-terminated messages flushing the MT queue or sending a ring alert can be sent
+    /// without one, and the gateway leaves it out of MO sessions that carried no message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sbd_lib::Message;
+    /// assert!(Message::from_path("data/0-mo.sbd").unwrap().has_payload());
+    /// ```
+    pub fn has_payload(&self) -> bool {
+        self.payload.is_some()
     }
 
     /// Create message from Path
@@ -69,8 +133,57 @@ impl Message {
     }
 
     /// Create message from Read
-    pub fn read_from<R: Read>(mut read: R) -> Result<Self> {
-        Self::create(InformationElement::parse(&mut read)?)
+    pub fn read_from<R: Read>(read: R) -> Result<Self> {
+        Self::read_from_with(read, ParseOptions::default())
+    }
+
+    /// Create message from Read with the given parse options
+    ///
+    /// Like `DirectIpMessage`, MO messages are accepted without a payload.
+    pub fn read_from_with<R: Read>(read: R, options: ParseOptions) -> Result<Self> {
+        Self::from_frame(&InformationElement::read_frame(read)?, options)
+    }
+
+    /// Create message from AsyncRead
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// use sbd_lib::Message;
+    /// let file = tokio::fs::File::open("data/0-mo.sbd").await.unwrap();
+    /// let message = Message::read_from_async(file).await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn read_from_async<R: AsyncRead + Unpin>(read: R) -> Result<Self> {
+        Self::read_from_async_with(read, ParseOptions::default()).await
+    }
+
+    /// Create message from AsyncRead with the given parse options
+    #[cfg(feature = "tokio")]
+    pub async fn read_from_async_with<R: AsyncRead + Unpin>(
+        read: R,
+        options: ParseOptions,
+    ) -> Result<Self> {
+        Self::from_frame(&InformationElement::read_frame_async(read).await?, options)
+    }
+
+    /// Parses a frame, as far as it was received, keeping the bytes lenient parsing skipped.
+    fn from_frame(frame: &[u8], options: ParseOptions) -> Result<Self> {
+        let ParsedFrame {
+            information_elements,
+            offsets,
+            skipped,
+        } = InformationElement::parse_frame(frame, options)?;
+        Ok(Self::assemble(
+            information_elements,
+            false,
+            options.is_strict(),
+            Some(&offsets),
+        )?
+        .with_skipped(skipped))
     }
 
     pub fn new(
@@ -81,19 +194,68 @@ impl Message {
     ) -> Self {
         Message {
             header,
-            payload,
+            payload: Some(payload),
             location,
+            priority: None,
             information_elements: ie,
+            order: Vec::new(),
+            skipped: Vec::new(),
         }
     }
 
+    pub(crate) fn from_parts(
+        header: Header,
+        payload: Option<Vec<u8>>,
+        location: Option<LocationInformation>,
+        priority: Option<mt::MtPriority>,
+        information_elements: Vec<InformationElement>,
+        order: Vec<IeSlot>,
+    ) -> Self {
+        Message {
+            header,
+            payload,
+            location,
+            priority,
+            information_elements,
+            order,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Sets the bytes lenient parsing skipped past the length of information elements, by
+    /// position in the order they were read.
+    pub(crate) fn with_skipped(mut self, skipped: Vec<(usize, Vec<u8>)>) -> Self {
+        self.skipped = skipped;
+        self
+    }
+
+    /// Splits this message into its header, payload, location, priority and other information
+    /// elements.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn into_parts(
+        self,
+    ) -> (
+        Header,
+        Option<Vec<u8>>,
+        Option<LocationInformation>,
+        Option<mt::MtPriority>,
+        Vec<InformationElement>,
+    ) {
+        (
+            self.header,
+            self.payload,
+            self.location,
+            self.priority,
+            self.information_elements,
+        )
+    }
+
     /// Return overall message length with header
     pub fn length(&self) -> usize {
         self.header.len()
-            + self.payload.len()
-            + 1
-            + 2
-            + self.location.and_then(|l| Some(l.len())).unwrap_or(0)
+            + self.payload.as_ref().map(|p| p.len() + 3).unwrap_or(0)
+            + self.location.map(|l| l.len()).unwrap_or(0)
+            + self.priority.map(|p| p.len()).unwrap_or(0)
             + self
                 .information_elements
                 .iter()
@@ -104,6 +266,10 @@ impl Message {
 
     /// Creates a new message from information elements.
     ///
+    /// Every information element must belong to the same direction as the header, e.g. an MT
+    /// header cannot come with an MO payload or a location. A payload is required, except for MT
+    /// messages flushing the MT queue or sending a ring alert.
+    ///
     /// # Examples
     ///
     /// ```
@@ -114,7 +280,7 @@ impl Message {
     ///         let header = InformationElement::Header(
     ///             mo::Header {
     ///                 auto_id: 1,
-    ///                 imei: [0; 15].into(),
+    ///                 imei: (*b"300234063904190").into(),
     ///                 session_status: mo::SessionStatus::Ok,
     ///                 momsn: 1,
     ///                 mtmsn: 0,
@@ -131,62 +297,121 @@ impl Message {
     /// # }
     /// ```
     pub fn create<I: IntoIterator<Item = InformationElement>>(iter: I) -> Result<Self> {
-        use crate::Error;
+        Self::assemble(iter, true, true, None)
+    }
+
+    /// Creates a new message from information elements, optionally allowing the MO payload to be
+    /// absent and information elements of the other direction than the header.
+    ///
+    /// The gateway omits the MO payload IE for sessions that carried no message (e.g. a mailbox
+    /// check that only reports a location). MT messages only need a payload when their
+    /// disposition flags do not make them meaningful without one.
+    ///
+    /// Information elements read from a frame come with their offsets, so errors are reported as
+    /// parse errors pointing at the faulty information element or at the whole message.
+    pub(crate) fn assemble<I: IntoIterator<Item = InformationElement>>(
+        iter: I,
+        payload_required: bool,
+        check_directions: bool,
+        offsets: Option<&[usize]>,
+    ) -> Result<Self> {
+        use crate::{
+            message_ref::{message_error, misplaced_error},
+            Error,
+        };
 
         let mut header: Option<Header> = None;
         let mut payload = None;
         let mut location = None;
+        let mut priority = None;
         let mut information_elements = Vec::new();
-        for information_element in iter {
-            match information_element {
+        let mut order = Vec::new();
+        let mut ieis = Vec::new();
+        let misplaced = |err, iei, position| match offsets {
+            Some(offsets) => misplaced_error(err, iei, offsets[position]),
+            None => err,
+        };
+        let message_error = |err| match offsets {
+            Some(_) => message_error(err),
+            None => err,
+        };
+        for (position, information_element) in iter.into_iter().enumerate() {
+            let iei = information_element.iei();
+            ieis.push(iei);
+            let slot = match information_element {
                 InformationElement::Header(h) => {
                     if header.is_some() {
-                        return Err(Error::TwoHeaders);
+                        return Err(misplaced(Error::TwoHeaders, iei, position));
                     } else {
                         header = Some(h);
                     }
+                    IeSlot::Header
                 }
                 InformationElement::MOPayload(p) | InformationElement::MTPayload(p) => {
                     if payload.is_some() {
-                        return Err(Error::TwoPayloads);
+                        return Err(misplaced(Error::TwoPayloads, iei, position));
                     } else {
                         payload = Some(p);
                     }
+                    IeSlot::Payload
                 }
                 InformationElement::LocationInformation(l) => {
                     if location.is_some() {
-                        return Err(Error::TwoLocations);
+                        return Err(misplaced(Error::TwoLocations, iei, position));
                     } else {
                         location = Some(l);
                     }
+                    IeSlot::Location
                 }
-                ie => information_elements.push(ie),
-            }
+                InformationElement::Priority(p) => {
+                    if priority.is_some() {
+                        return Err(misplaced(Error::TwoPriorities, iei, position));
+                    } else {
+                        priority = Some(p);
+                    }
+                    IeSlot::Priority
+                }
+                ie => {
+                    information_elements.push(ie);
+                    IeSlot::Other(information_elements.len() - 1)
+                }
+            };
+            order.push(slot);
         }
 
-        Ok(Self::new(
-            header.ok_or(Error::NoHeader)?,
-            payload.ok_or(Error::NoPayload)?,
+        let header = header.ok_or_else(|| message_error(Error::NoHeader))?;
+        if check_directions {
+            crate::message_ref::check_directions(&header, ieis.iter().copied())
+                .map_err(|(position, err)| misplaced(err, ieis[position], position))?;
+        }
+        let payload_required = match header {
+            Header::MOHeader(_) => payload_required,
+            Header::MTHeader(header) => header.flags.requires_payload(),
+        };
+        if payload_required && payload.is_none() {
+            return Err(message_error(Error::NoPayload));
+        }
+
+        Ok(Self::from_parts(
+            header,
+            payload,
             location,
+            priority,
             information_elements,
+            order,
         ))
     }
 
-    /// Returns this message's imei as a string.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the IMEI number is not valid utf8. The specification says that IMEIs should be
-    /// ascii numbers.
+    /// Returns this message's imei.
     ///
     /// # Examples
     ///
     /// ```
     /// use sbd_lib::Message;
     /// let message = Message::from_path("data/0-mo.sbd").unwrap();
-    /// let imei = message.imei();
+    /// assert_eq!("300234063904190", message.imei());
     /// ```
-    pub fn imei(&self) -> &str {
+    pub fn imei(&self) -> &Imei {
         self.header().imei()
     }
 
@@ -200,15 +425,67 @@ impl Message {
     /// let mut buff = vec![];
     /// message.write_to(&mut buff);
     /// ```
-    pub fn write_to<W: Write>(&self, mut write: W) -> Result<()> {
-        use byteorder::{BigEndian, WriteBytesExt};
+    pub fn write_to<W: Write>(&self, write: W) -> Result<()> {
+        let others = (0..self.information_elements.len()).map(IeSlot::Other);
+        let slots = IeSlot::CANONICAL.iter().copied().chain(others);
+        self.write_slots(write, slots.map(|slot| (slot, &[][..])), 0)
+    }
 
-        let payload = match self.header {
-            Header::MOHeader(_) => InformationElement::MOPayload(self.payload.clone()),
-            Header::MTHeader(_) => InformationElement::MTPayload(self.payload.clone()),
+    /// Write this message back in the order its information elements were read.
+    ///
+    /// Reading a message and writing it back this way reproduces the original frame byte for
+    /// byte, including the bytes lenient parsing skipped past the length an information element
+    /// requires. Information elements added after reading, or messages that were not read, are written in
+    /// the same order as `write_to`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sbd_lib::Message;
+    /// let data = std::fs::read("data/1-mo-location.sbd").unwrap();
+    /// let message = Message::read_from(data.as_slice()).unwrap();
+    /// let mut buff = vec![];
+    /// message.write_to_original_order(&mut buff).unwrap();
+    /// assert_eq!(data, buff);
+    /// ```
+    pub fn write_to_original_order<W: Write>(&self, write: W) -> Result<()> {
+        let others = (0..self.information_elements.len()).map(IeSlot::Other);
+        let missing = IeSlot::CANONICAL
+            .iter()
+            .copied()
+            .chain(others)
+            .filter(|slot| !self.order.contains(slot));
+        let skipped = |position| {
+            self.skipped
+                .iter()
+                .find(|(p, _)| *p == position)
+                .map_or(&[][..], |(_, skipped)| skipped.as_slice())
         };
+        let read = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(position, &slot)| (slot, skipped(position)));
+        let extra = self.skipped.iter().map(|(_, skipped)| skipped.len()).sum();
+        self.write_slots(
+            write,
+            read.chain(missing.map(|slot| (slot, &[][..]))),
+            extra,
+        )
+    }
+
+    /// Writes the protocol header, then the information elements in the given slots followed by
+    /// the given skipped bytes, which add up to `extra` bytes.
+    ///
+    /// Empty slots are skipped.
+    fn write_slots<'a, W, I>(&self, mut write: W, slots: I, extra: usize) -> Result<()>
+    where
+        W: Write,
+        I: Iterator<Item = (IeSlot, &'a [u8])>,
+    {
+        use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
 
-        let overall_message_length = self.length() - 3;
+        let overall_message_length = self.length() - 3 + extra;
 
         if overall_message_length > u16::MAX as usize {
             return Err(crate::Error::OverallMessageLength(overall_message_length));
@@ -216,20 +493,98 @@ impl Message {
 
         write.write_u8(PROTOCOL_REVISION_NUMBER)?;
         write.write_u16::<BigEndian>(overall_message_length as u16)?;
-        self.header.write_to(&mut write)?;
-        payload.write_to(&mut write)?;
-        self.location.and_then(|l| l.write_to(&mut write).ok());
-        for information_element in &self.information_elements {
-            information_element.write_to(&mut write)?;
+        for (slot, skipped) in slots {
+            if skipped.is_empty() {
+                self.write_slot(slot, &mut write)?;
+            } else {
+                // Written with the length it was declared with, followed by the skipped bytes.
+                let mut buff = Vec::new();
+                self.write_slot(slot, &mut buff)?;
+                let length = BigEndian::read_u16(&buff[1..3]) as usize + skipped.len();
+                BigEndian::write_u16(&mut buff[1..3], length as u16);
+                write.write_all(&buff)?;
+                write.write_all(skipped)?;
+            }
         }
         Ok(())
     }
 
+    /// Writes the information element in a slot, if any.
+    fn write_slot<W: Write>(&self, slot: IeSlot, mut write: W) -> Result<()> {
+        match slot {
+            IeSlot::Header => self.header.write_to(&mut write)?,
+            IeSlot::Payload => {
+                if let Some(payload) = self.payload.clone() {
+                    match self.header {
+                        Header::MOHeader(_) => InformationElement::MOPayload(payload),
+                        Header::MTHeader(_) => InformationElement::MTPayload(payload),
+                    }
+                    .write_to(&mut write)?;
+                }
+            }
+            IeSlot::Location => {
+                if let Some(location) = self.location {
+                    location.write_to(&mut write)?;
+                }
+            }
+            IeSlot::Priority => {
+                if let Some(priority) = self.priority {
+                    priority.write_to(&mut write)?;
+                }
+            }
+            IeSlot::Other(index) => {
+                if let Some(information_element) = self.information_elements.get(index) {
+                    information_element.write_to(&mut write)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write this message to an object that can `AsyncWrite`.
+    ///
+    /// The message is validated and serialized up front, so nothing is written if it is invalid.
+    #[cfg(feature = "tokio")]
+    pub async fn write_to_async<W: AsyncWrite + Unpin>(&self, mut write: W) -> Result<()> {
+        let mut buff = Vec::with_capacity(self.length());
+        self.write_to(&mut buff)?;
+        write.write_all(&buff).await?;
+        Ok(())
+    }
+
     /// Returns this message's location.
     pub fn location(&self) -> &Option<LocationInformation> {
         &self.location
     }
 
+    /// Returns this message's priority.
+    pub fn priority(&self) -> Option<mt::MtPriority> {
+        self.priority
+    }
+
+    /// Sets the priority of this mobile-terminated message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sbd_lib::{mt, Message};
+    /// let header = mt::Header {
+    ///     message_id: 1,
+    ///     imei: "300434060009290".parse().unwrap(),
+    ///     flags: mt::DispositionFlags::empty(),
+    /// };
+    /// let message = Message::new(header.into(), b"reboot".to_vec(), None, vec![])
+    ///     .with_priority(mt::MtPriority::HIGHEST);
+    /// assert_eq!(Some(mt::MtPriority::HIGHEST), message.priority());
+    /// ```
+    pub fn with_priority(mut self, priority: mt::MtPriority) -> Self {
+        self.priority = Some(priority);
+        let order = &self.order;
+        self.skipped
+            .retain(|&(position, _)| order[position] != IeSlot::Priority);
+        self
+    }
+
     /// Returns this message's information_elements.
     pub fn information_elements(&self) -> &[InformationElement] {
         &self.information_elements
@@ -245,14 +600,14 @@ impl From<Header> for Message {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{mo, mt};
+    use crate::{mo, mt, MessageRef};
     use std::{fs::File, io::Cursor};
     use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
 
     fn mo_header() -> mo::Header {
         mo::Header {
             auto_id: 1,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
+            imei: (*b"300234063904190").into(),
             session_status: mo::SessionStatus::Ok,
             momsn: 1,
             mtmsn: 0,
@@ -264,6 +619,14 @@ mod tests {
         }
     }
 
+    fn mt_header() -> mt::Header {
+        mt::Header {
+            message_id: 1,
+            imei: (*b"300434060009290").into(),
+            flags: mt::DispositionFlags::empty(),
+        }
+    }
+
     fn location() -> mo::LocationInformation {
         mo::LocationInformation::new(0, (43, 30854), (41, 48860), Some(3))
     }
@@ -321,13 +684,221 @@ mod tests {
     #[test]
     fn two_headers() {
         let header = mo_header();
-        assert!(Message::create(vec![header.clone().into(), header.into()]).is_err());
+        assert!(Message::create(vec![header.into(), header.into()]).is_err());
     }
 
     #[test]
     fn two_location() {
         let location = location();
-        assert!(Message::create(vec![location.clone().into(), location.into()]).is_err());
+        assert!(Message::create(vec![location.into(), location.into()]).is_err());
+    }
+
+    #[test]
+    fn two_priorities() {
+        let priority = mt::MtPriority::HIGHEST;
+        assert!(matches!(
+            Message::create(vec![
+                mt_header().into(),
+                InformationElement::MTPayload(vec![]),
+                priority.into(),
+                priority.into()
+            ])
+            .as_ref()
+            .map_err(crate::Error::root),
+            Err(crate::Error::TwoPriorities)
+        ));
+    }
+
+    #[test]
+    fn header_only_mt() {
+        let header = |flags| mt::Header {
+            message_id: 1,
+            imei: (*b"300234063904190").into(),
+            flags,
+        };
+        assert!(matches!(
+            Message::create(vec![header(mt::DispositionFlags::empty()).into()]),
+            Err(crate::Error::NoPayload)
+        ));
+
+        let message =
+            Message::create(vec![header(mt::DispositionFlags::SEND_RING_ALERT).into()]).unwrap();
+        assert!(!message.has_payload());
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+        assert_eq!(message.length(), buff.len());
+        assert_eq!(27, buff.len());
+        assert_eq!(message, Message::read_from(buff.as_slice()).unwrap());
+        assert!(!MessageRef::parse(&buff).unwrap().has_payload());
+
+        buff[25..27].copy_from_slice(&[0, 0]);
+        for err in [
+            Message::read_from(buff.as_slice()).unwrap_err(),
+            MessageRef::parse(&buff).unwrap_err(),
+        ] {
+            assert!(matches!(err.root(), crate::Error::NoPayload));
+            assert_eq!(
+                (Some(3), None, Some("information_elements")),
+                (err.offset(), err.iei(), err.field())
+            );
+        }
+
+        let message = Message::create(vec![
+            header(mt::DispositionFlags::FLUSH_MT_QUEUE).into(),
+            InformationElement::MTPayload(Vec::new()),
+        ])
+        .unwrap();
+        assert!(message.has_payload());
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+        assert_eq!(&[0x42, 0, 0], &buff[27..]);
+    }
+
+    #[test]
+    fn direction_mismatch() {
+        use crate::{DirectIpMessage, Error};
+
+        let mismatched = |information_elements: Vec<InformationElement>| match Message::create(
+            information_elements,
+        ) {
+            Err(Error::DirectionMismatch {
+                iei,
+                mobile_originated,
+            }) => Some((iei, mobile_originated)),
+            _ => None,
+        };
+        assert_eq!(
+            Some((0x02, false)),
+            mismatched(vec![
+                mt_header().into(),
+                InformationElement::MOPayload(b"reboot".to_vec()),
+            ])
+        );
+        assert_eq!(
+            Some((0x03, false)),
+            mismatched(vec![
+                mt_header().into(),
+                InformationElement::MTPayload(b"reboot".to_vec()),
+                location().into(),
+            ])
+        );
+        assert_eq!(
+            Some((0x42, true)),
+            mismatched(vec![
+                mo_header().into(),
+                InformationElement::MTPayload(b"hello".to_vec()),
+            ])
+        );
+        assert_eq!(
+            Some((0x46, true)),
+            mismatched(vec![
+                mt::MtPriority::HIGHEST.into(),
+                mo_header().into(),
+                InformationElement::MOPayload(b"hello".to_vec()),
+            ])
+        );
+
+        let mut data = std::fs::read("data/0-mo.sbd").unwrap();
+        data[34] = 0x42;
+        assert!(Message::read_from(data.as_slice()).is_ok());
+        let strict = ParseOptions::strict();
+        for err in [
+            Message::read_from_with(data.as_slice(), strict).unwrap_err(),
+            MessageRef::parse_with(&data, strict).unwrap_err(),
+            DirectIpMessage::read_from_with(data.as_slice(), strict).unwrap_err(),
+        ] {
+            assert!(
+                matches!(
+                    err.root(),
+                    Error::DirectionMismatch {
+                        iei: 0x42,
+                        mobile_originated: true
+                    }
+                ),
+                "{}",
+                err
+            );
+            assert_eq!(
+                (Some(34), Some(0x42), Some("iei")),
+                (err.offset(), err.iei(), err.field())
+            );
+        }
+    }
+
+    #[test]
+    fn message_errors_are_located() {
+        use crate::{DirectIpMessage, Error};
+
+        let data = std::fs::read("data/0-mo.sbd").unwrap();
+        let mut two_headers = data.clone();
+        two_headers.extend_from_slice(&data[3..34]);
+        two_headers[2] += 31;
+        let no_header = [1, 0, 3, 0x02, 0, 0];
+        for (data, offset, iei, field) in [
+            (two_headers.as_slice(), 59, Some(0x01), "iei"),
+            (&no_header[..], 3, None, "information_elements"),
+        ] {
+            for err in [
+                Message::read_from(data).unwrap_err(),
+                MessageRef::parse(data).unwrap_err(),
+                DirectIpMessage::read_from(data).unwrap_err(),
+            ] {
+                assert!(
+                    matches!(err.root(), Error::TwoHeaders | Error::NoHeader),
+                    "{}",
+                    err
+                );
+                assert_eq!(
+                    (Some(offset), iei, Some(field)),
+                    (err.offset(), err.iei(), err.field())
+                );
+            }
+        }
+        assert!(matches!(
+            Message::create(vec![InformationElement::MOPayload(vec![])]),
+            Err(Error::NoHeader)
+        ));
+    }
+
+    #[test]
+    fn mt_priority() {
+        let message = Message::create(vec![
+            mt::MtPriority::new(2).unwrap().into(),
+            mt_header().into(),
+            InformationElement::MTPayload(b"reboot".to_vec()),
+        ])
+        .unwrap();
+        assert_eq!(Some(mt::MtPriority::new(2).unwrap()), message.priority());
+        assert!(message.information_elements().is_empty());
+        assert!(message.to_string().ends_with(", priority: 2"));
+
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+        assert_eq!(message.length(), buff.len());
+        assert_eq!(&[0x46, 0, 2, 0, 2], &buff[buff.len() - 5..]);
+        assert_eq!(message, Message::read_from(buff.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn unknown_session_status() {
+        let mut data = std::fs::read("data/0-mo.sbd").unwrap();
+        data[25] = 3;
+        let message = Message::read_from(data.as_slice()).unwrap();
+        let header = message.header().as_mo().unwrap();
+        assert_eq!(mo::SessionStatus::from(3), header.session_status);
+        assert!(!header.session_status.is_ok());
+        assert_eq!(b"test message from pete", message.payload());
+
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+        assert_eq!(data, buff);
+
+        assert!(matches!(
+            Message::read_from_with(data.as_slice(), ParseOptions::strict())
+                .as_ref()
+                .map_err(crate::Error::root),
+            Err(crate::Error::UnknownSessionStatus(3))
+        ));
     }
 
     #[test]
@@ -420,6 +991,168 @@ mod tests {
         assert_eq!(message, read_back);
     }
 
+    #[test]
+    fn unknown_information_element() {
+        let file = File::open("data/0-mo.sbd").unwrap();
+        let mut information_elements = InformationElement::parse(file).unwrap();
+        let unknown = InformationElement::Unknown {
+            iei: 0x7f,
+            data: vec![0xde, 0xad],
+        };
+        information_elements.insert(1, unknown.clone());
+        let message = Message::create(information_elements).unwrap();
+        assert_eq!(&[unknown], message.information_elements());
+
+        let mut buff = vec![];
+        message.write_to(&mut buff).unwrap();
+        assert_eq!(message, Message::read_from(Cursor::new(buff)).unwrap());
+    }
+
+    #[test]
+    fn original_order() {
+        let unknown = InformationElement::Unknown {
+            iei: 0x7f,
+            data: vec![0xde, 0xad],
+        };
+        let information_elements = vec![
+            unknown.clone(),
+            InformationElement::MTPayload(b"reboot".to_vec()),
+            mt::MtPriority::HIGHEST.into(),
+            mt_header().into(),
+        ];
+        let mut expected = vec![1, 0, 43];
+        for information_element in &information_elements {
+            information_element.write_to(&mut expected).unwrap();
+        }
+
+        let message = Message::read_from(expected.as_slice()).unwrap();
+        let mut buff = vec![];
+        message.write_to_original_order(&mut buff).unwrap();
+        assert_eq!(expected, buff);
+
+        let mut canonical = vec![];
+        message.write_to(&mut canonical).unwrap();
+        assert_ne!(expected, canonical);
+        let reordered = Message::read_from(canonical.as_slice()).unwrap();
+        assert_eq!(message, reordered);
+
+        let message = MessageRef::parse(&expected).unwrap().to_owned();
+        let mut buff = vec![];
+        message.write_to_original_order(&mut buff).unwrap();
+        assert_eq!(expected, buff);
+    }
+
+    #[test]
+    fn original_order_over_long() {
+        let data = std::fs::read("data/0-mo.sbd").unwrap();
+        let mut over_long = data[..34].to_vec();
+        over_long[2] += 2;
+        over_long[5] = 30;
+        over_long.extend_from_slice(&[0xaa, 0xbb]);
+        over_long.extend_from_slice(&data[34..]);
+
+        let message = Message::read_from(over_long.as_slice()).unwrap();
+        let mut buff = vec![];
+        message.write_to_original_order(&mut buff).unwrap();
+        assert_eq!(over_long, buff);
+        let mut canonical = vec![];
+        message.write_to(&mut canonical).unwrap();
+        assert_eq!(data, canonical);
+
+        let mut buff = vec![];
+        MessageRef::parse(&over_long)
+            .unwrap()
+            .to_owned()
+            .write_to_original_order(&mut buff)
+            .unwrap();
+        assert_eq!(over_long, buff);
+    }
+
+    #[test]
+    fn original_order_after_change() {
+        let mut data = vec![];
+        Message::new(mt_header().into(), b"reboot".to_vec(), None, vec![])
+            .write_to(&mut data)
+            .unwrap();
+        let message = Message::read_from(data.as_slice())
+            .unwrap()
+            .with_priority(mt::MtPriority::LOWEST);
+        let mut buff = vec![];
+        message.write_to_original_order(&mut buff).unwrap();
+        let mut expected = vec![];
+        message.write_to(&mut expected).unwrap();
+        assert_eq!(expected, buff);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_roundtrip() {
+        let data = std::fs::read("data/1-mo-location.sbd").unwrap();
+        let message = Message::read_from_async(data.as_slice()).await.unwrap();
+        assert_eq!(
+            Message::from_path("data/1-mo-location.sbd").unwrap(),
+            message
+        );
+
+        let mut buff = vec![];
+        message.write_to_async(&mut buff).await.unwrap();
+        let mut expected = vec![];
+        message.write_to(&mut expected).unwrap();
+        assert_eq!(expected, buff);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_truncated() {
+        let data = std::fs::read("data/0-mo.sbd").unwrap();
+        for len in 0..data.len() {
+            assert!(Message::read_from_async(&data[..len]).await.is_err());
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_strict() {
+        use crate::Error;
+
+        let strict = ParseOptions::strict();
+        let mut data = std::fs::read("data/0-mo.sbd").unwrap();
+        data[34] = 0x42;
+        assert!(Message::read_from_async(data.as_slice()).await.is_ok());
+        let err = Message::read_from_async_with(data.as_slice(), strict)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            Error::DirectionMismatch { iei: 0x42, .. }
+        ));
+        assert_eq!(Some(34), err.offset());
+
+        let data = std::fs::read("data/0-mo.sbd").unwrap();
+        let err = Message::read_from_async_with(data.as_slice(), strict.verify_check_digit(true))
+            .await
+            .unwrap_err();
+        assert!(matches!(err.root(), Error::InvalidImei(_)));
+
+        let mut header = vec![];
+        InformationElement::from(mt::Header {
+            flags: mt::DispositionFlags::from_bits_retain(0x8000),
+            ..mt_header()
+        })
+        .write_to(&mut header)
+        .unwrap();
+        assert!(InformationElement::read_single_async(header.as_slice())
+            .await
+            .is_ok());
+        let err = InformationElement::read_single_async_with(header.as_slice(), strict)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            Error::ReservedDispositionFlags(0x8000)
+        ));
+    }
+
     #[test]
     fn sbd_responce_for_mt() {
         let file = File::open("data/resp.sbd").unwrap();
@@ -429,7 +1162,7 @@ mod tests {
                 message_id: 287454020,
                 imei: (*b"300434060009290").into(),
                 auto_id: 3064195606,
-                status: 1,
+                status: mt::MtStatus::QueuePosition(1),
             }
             .into(),
         );
@@ -0,0 +1,372 @@
+use crate::{DirectIpMessage, Error, Message, Result};
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// The default read and write timeout of accepted connections.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The default number of connections `MoServer::serve` handles at the same time.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+
+/// How long `MoServer::serve` waits after a first failed accept, doubled on every further failure.
+const MIN_ACCEPT_BACKOFF: Duration = Duration::from_millis(10);
+
+/// The longest `MoServer::serve` waits after a failed accept.
+const MAX_ACCEPT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Handles mobile-originated messages received by a `MoServer`.
+///
+/// Closures taking a `Message` and returning a `bool` are handlers too.
+pub trait MoHandler: Send + Sync {
+    /// Handles a message, returning whether it was accepted.
+    ///
+    /// The result is sent back to the gateway as the MO confirmation.
+    fn handle(&self, message: Message) -> bool;
+
+    /// Called by `MoServer::serve` with errors it cannot return, from accepting or handling a
+    /// connection.
+    ///
+    /// Ignores them by default.
+    fn handle_error(&self, error: Error) {
+        let _ = error;
+    }
+}
+
+impl<F> MoHandler for F
+where
+    F: Fn(Message) -> bool + Send + Sync,
+{
+    fn handle(&self, message: Message) -> bool {
+        self(message)
+    }
+}
+
+/// Reads a mobile-originated message from `stream`, passes it to `handler` and writes back the
+/// MO confirmation.
+///
+/// A message that cannot be read or is not mobile-originated is confirmed as failed, and the
+/// error is returned.
+pub fn handle_connection<S, H>(mut stream: S, handler: &H) -> Result<()>
+where
+    S: Read + Write,
+    H: MoHandler + ?Sized,
+{
+    let (status, result) = match DirectIpMessage::read_from(&mut stream) {
+        Ok(DirectIpMessage::MoMessage(message)) => (handler.handle(message), Ok(())),
+        Ok(_) => (false, Err(Error::UnexpectedMessage)),
+        Err(err) => (false, Err(err)),
+    };
+    DirectIpMessage::mo_confirmation(status).write_to(&mut stream)?;
+    stream.flush()?;
+    result
+}
+
+/// A DirectIP server receiving mobile-originated messages from the Iridium gateway.
+///
+/// The gateway opens one connection per message, so every connection is handled by reading a
+/// single message and answering with its confirmation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sbd_lib::server::MoServer;
+/// use sbd_lib::Message;
+///
+/// let server = MoServer::bind("0.0.0.0:10800", |message: Message| {
+///     println!("{}", message);
+///     true
+/// })
+/// .unwrap();
+/// server.serve();
+/// ```
+#[derive(Debug)]
+pub struct MoServer<H> {
+    listener: TcpListener,
+    handler: Arc<H>,
+    timeout: Option<Duration>,
+    max_connections: usize,
+}
+
+impl<H: MoHandler + 'static> MoServer<H> {
+    /// Binds a new server to `addr`.
+    pub fn bind<A: ToSocketAddrs>(addr: A, handler: H) -> Result<Self> {
+        Ok(MoServer {
+            listener: TcpListener::bind(addr)?,
+            handler: Arc::new(handler),
+            timeout: Some(DEFAULT_TIMEOUT),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+        })
+    }
+
+    /// Sets the read and write timeout of accepted connections, `DEFAULT_TIMEOUT` by default.
+    ///
+    /// Without a timeout, clients that connect and never send anything hold their handler thread
+    /// forever.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many connections `serve` handles at the same time, `DEFAULT_MAX_CONNECTIONS` by
+    /// default.
+    ///
+    /// Further connections wait in the listen backlog until a handler thread is done.
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections.max(1);
+        self
+    }
+
+    /// Returns the address this server is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Accepts and handles a single connection on the current thread.
+    pub fn accept(&self) -> Result<()> {
+        let (stream, _) = self.listener.accept()?;
+        self.handle(stream)
+    }
+
+    /// Accepts connections forever, handling each one on its own thread.
+    ///
+    /// Errors of individual connections are already reported to the gateway through the MO
+    /// confirmation, so they do not stop the server. They are passed to
+    /// `MoHandler::handle_error`, along with failed accepts, after which the server waits a
+    /// little longer each time before accepting again (e.g. while out of file descriptors).
+    pub fn serve(&self) {
+        let limit = Arc::new(ConnectionLimit::new(self.max_connections));
+        let mut backoff = MIN_ACCEPT_BACKOFF;
+        loop {
+            let permit = ConnectionLimit::acquire(&limit);
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    self.handler.handle_error(err.into());
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(MAX_ACCEPT_BACKOFF);
+                    continue;
+                }
+            };
+            backoff = MIN_ACCEPT_BACKOFF;
+            let handler = Arc::clone(&self.handler);
+            let timeout = self.timeout;
+            thread::spawn(move || {
+                let _permit = permit;
+                if let Err(err) = Self::configure(&stream, timeout)
+                    .and_then(|_| handle_connection(stream, &*handler))
+                {
+                    handler.handle_error(err);
+                }
+            });
+        }
+    }
+
+    fn handle(&self, stream: TcpStream) -> Result<()> {
+        Self::configure(&stream, self.timeout)?;
+        handle_connection(stream, &*self.handler)
+    }
+
+    fn configure(stream: &TcpStream, timeout: Option<Duration>) -> Result<()> {
+        stream.set_read_timeout(timeout)?;
+        stream.set_write_timeout(timeout)?;
+        Ok(())
+    }
+}
+
+/// Counts the connections being handled, making `acquire` wait while there are too many.
+#[derive(Debug)]
+struct ConnectionLimit {
+    max: usize,
+    active: Mutex<usize>,
+    released: Condvar,
+}
+
+/// A connection counted by a `ConnectionLimit`, until dropped.
+#[derive(Debug)]
+struct Permit(Arc<ConnectionLimit>);
+
+impl ConnectionLimit {
+    fn new(max: usize) -> Self {
+        ConnectionLimit {
+            max,
+            active: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(limit: &Arc<Self>) -> Permit {
+        let mut active = limit.active.lock().unwrap();
+        while *active >= limit.max {
+            active = limit.released.wait(active).unwrap();
+        }
+        *active += 1;
+        Permit(Arc::clone(limit))
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        *self.0.active.lock().unwrap() -= 1;
+        self.0.released.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{self, Receiver, Sender};
+
+    fn send(addr: SocketAddr, data: &[u8]) -> DirectIpMessage {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(data).unwrap();
+        DirectIpMessage::read_from(stream).unwrap()
+    }
+
+    #[test]
+    fn replay_fixtures() {
+        let paths = [
+            "data/0-mo.sbd",
+            "data/1-mo-location.sbd",
+            "data/data.sbd",
+            "data/iridium.ack",
+        ];
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let server = {
+            let received = Arc::clone(&received);
+            MoServer::bind("127.0.0.1:0", move |message| {
+                received.lock().unwrap().push(message);
+                true
+            })
+            .unwrap()
+            .timeout(Some(Duration::from_secs(5)))
+        };
+        let addr = server.local_addr().unwrap();
+        let accept = thread::spawn(move || {
+            for _ in 0..paths.len() {
+                server.accept().unwrap();
+            }
+        });
+
+        for path in &paths {
+            let data = std::fs::read(path).unwrap();
+            assert_eq!(DirectIpMessage::mo_confirmation(true), send(addr, &data));
+        }
+        accept.join().unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(paths.len(), received.len());
+        for (path, message) in paths.iter().zip(received.iter()) {
+            match DirectIpMessage::from_path(path).unwrap() {
+                DirectIpMessage::MoMessage(expected) => assert_eq!(&expected, message),
+                _ => panic!("Unexpected message"),
+            }
+        }
+    }
+
+    #[test]
+    fn rejected_by_handler() {
+        let server = MoServer::bind("127.0.0.1:0", |_| false).unwrap();
+        let addr = server.local_addr().unwrap();
+        let accept = thread::spawn(move || server.accept());
+
+        let data = std::fs::read("data/0-mo.sbd").unwrap();
+        assert_eq!(DirectIpMessage::mo_confirmation(false), send(addr, &data));
+        assert!(accept.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn not_mobile_originated() {
+        let server = MoServer::bind("127.0.0.1:0", |_| true).unwrap();
+        let addr = server.local_addr().unwrap();
+        let accept = thread::spawn(move || server.accept());
+
+        let data = std::fs::read("data/resp.sbd").unwrap();
+        assert_eq!(DirectIpMessage::mo_confirmation(false), send(addr, &data));
+        assert!(matches!(
+            accept.join().unwrap(),
+            Err(Error::UnexpectedMessage)
+        ));
+    }
+
+    /// Forwards messages and errors to the test, accepting every message.
+    struct Forward(Mutex<Sender<Result<Message>>>);
+
+    impl MoHandler for Forward {
+        fn handle(&self, message: Message) -> bool {
+            self.0.lock().unwrap().send(Ok(message)).unwrap();
+            true
+        }
+
+        fn handle_error(&self, error: Error) {
+            self.0.lock().unwrap().send(Err(error)).unwrap();
+        }
+    }
+
+    fn serve(max_connections: usize, timeout: Duration) -> (SocketAddr, Receiver<Result<Message>>) {
+        let (sender, receiver) = mpsc::channel();
+        let server = MoServer::bind("127.0.0.1:0", Forward(Mutex::new(sender)))
+            .unwrap()
+            .max_connections(max_connections)
+            .timeout(Some(timeout));
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.serve());
+        (addr, receiver)
+    }
+
+    #[test]
+    fn serve_reports_errors() {
+        let (addr, received) = serve(DEFAULT_MAX_CONNECTIONS, DEFAULT_TIMEOUT);
+        let data = std::fs::read("data/resp.sbd").unwrap();
+        assert_eq!(DirectIpMessage::mo_confirmation(false), send(addr, &data));
+        assert!(matches!(
+            received.recv().unwrap(),
+            Err(Error::UnexpectedMessage)
+        ));
+    }
+
+    #[test]
+    fn serve_limits_connections() {
+        let (addr, received) = serve(1, DEFAULT_TIMEOUT);
+        let silent = TcpStream::connect(addr).unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(&std::fs::read("data/0-mo.sbd").unwrap())
+            .unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        assert!(stream.read(&mut [0; 1]).is_err());
+
+        drop(silent);
+        assert!(received.recv().unwrap().is_err());
+        assert!(received.recv().unwrap().is_ok());
+        stream.set_read_timeout(None).unwrap();
+        assert_eq!(
+            DirectIpMessage::mo_confirmation(true),
+            DirectIpMessage::read_from(stream).unwrap()
+        );
+    }
+
+    #[test]
+    fn silent_client_is_dropped() {
+        let server = MoServer::bind("127.0.0.1:0", |_| true).unwrap();
+        assert_eq!(Some(DEFAULT_TIMEOUT), server.timeout);
+
+        let (addr, received) = serve(1, Duration::from_millis(100));
+        let silent = TcpStream::connect(addr).unwrap();
+        assert!(received.recv().unwrap().is_err());
+        assert_eq!(
+            DirectIpMessage::mo_confirmation(false),
+            DirectIpMessage::read_from(silent).unwrap()
+        );
+
+        let data = std::fs::read("data/0-mo.sbd").unwrap();
+        assert_eq!(DirectIpMessage::mo_confirmation(true), send(addr, &data));
+    }
+}
@@ -0,0 +1,632 @@
+//! Collects every problem of a frame instead of stopping at the first one.
+//!
+//! Parsing fails fast, which is what a server answering the gateway needs. When triaging a
+//! capture, `validate` walks the whole frame and reports every error and warning it finds, along
+//! with the message that can still be read from it.
+
+use crate::message_ref::{
+    frame_length, is_mobile_originated, unexpected_eof, value_error, InformationElementRef,
+};
+use crate::sbd_message::IeSlot;
+use crate::{Error, Header, InformationElement, Message, ParseOptions, SbdHeader};
+use byteorder::{BigEndian, ByteOrder};
+use std::fmt;
+
+/// How serious a validation issue is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The frame does not follow the `DirectIP` documentation or looks wrong, but lenient parsing
+    /// accepts it.
+    Warning,
+    /// The frame, or part of it, cannot be read.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found in a frame.
+#[derive(Debug)]
+pub struct Issue {
+    severity: Severity,
+    error: Error,
+}
+
+impl Issue {
+    fn error(error: Error) -> Self {
+        Issue {
+            severity: Severity::Error,
+            error,
+        }
+    }
+
+    fn warning(error: Error) -> Self {
+        Issue {
+            severity: Severity::Warning,
+            error,
+        }
+    }
+
+    /// Returns how serious this issue is.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the problem itself, with where it was found.
+    pub fn as_error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Returns the offset in the frame this issue was found at.
+    ///
+    /// Issues about the message as a whole, e.g. a missing header, have no offset.
+    pub fn offset(&self) -> Option<usize> {
+        self.error.offset()
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.error)
+    }
+}
+
+/// Every issue found in a frame, and the message that could be read from it.
+#[derive(Debug)]
+pub struct ValidationReport {
+    issues: Vec<Issue>,
+    message: Option<Message>,
+}
+
+impl ValidationReport {
+    /// Returns every issue: the ones found reading the frame in order, then the ones about the
+    /// message as a whole.
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    /// Returns the issues preventing parts of the frame from being read.
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// Returns the issues lenient parsing accepts.
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    /// Returns true if no errors were found, warnings aside.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Returns the message read from the frame, if it has a header.
+    ///
+    /// Information elements that could not be read, duplicates and information elements of the
+    /// wrong direction are left out.
+    pub fn message(&self) -> Option<&Message> {
+        self.message.as_ref()
+    }
+
+    /// Returns the message read from the frame, if it has a header.
+    pub fn into_message(self) -> Option<Message> {
+        self.message
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Validates a whole frame, reporting every issue instead of stopping at the first one.
+///
+/// The MO time of session is unsigned on the wire, so a negative timestamp can only show up with
+/// its top bit set; such values are reported as `Error::NegativeTimestamp` warnings.
+///
+/// # Examples
+///
+/// ```
+/// use sbd_lib::validation::{validate, Severity};
+/// let data = std::fs::read("data/data.sbd").unwrap();
+/// let report = validate(&data);
+/// assert!(report.is_valid());
+/// assert_eq!(Severity::Warning, report.issues()[0].severity());
+/// assert!(report.message().is_some());
+/// ```
+pub fn validate(data: &[u8]) -> ValidationReport {
+    let mut issues = Vec::new();
+    let message = read_frame(data, &mut issues).and_then(|ies| assemble(ies, &mut issues));
+    ValidationReport { issues, message }
+}
+
+/// An information element read from a frame, with its identifier and offset.
+struct Located {
+    offset: usize,
+    iei: u8,
+    information_element: InformationElement,
+}
+
+/// Reads every information element that can be read, skipping the ones with invalid values.
+///
+/// Returns `None` if the protocol header cannot be read.
+fn read_frame(data: &[u8], issues: &mut Vec<Issue>) -> Option<Vec<Located>> {
+    let end = match frame_length(data) {
+        Ok(Some(end)) => end,
+        Ok(None) if data.is_empty() => {
+            issues.push(Issue::error(unexpected_eof().at(
+                0,
+                None,
+                "protocol_revision_number",
+            )));
+            return None;
+        }
+        Ok(None) => {
+            issues.push(Issue::error(unexpected_eof().at(
+                1,
+                None,
+                "overall_message_length",
+            )));
+            return None;
+        }
+        Err(err) => {
+            issues.push(Issue::error(err));
+            return None;
+        }
+    };
+    let frame = &data[..end.min(data.len())];
+
+    let mut information_elements = Vec::new();
+    let mut offset = Message::HEADER_SIZE;
+    while offset < end {
+        let rest = &frame[offset..];
+        let iei = match rest.first() {
+            Some(&iei) => iei,
+            None => {
+                issues.push(Issue::error(unexpected_eof().at(offset, None, "iei")));
+                break;
+            }
+        };
+        if rest.len() < 3 {
+            issues.push(Issue::error(unexpected_eof().at(
+                offset + 1,
+                Some(iei),
+                "length",
+            )));
+            break;
+        }
+        let length = BigEndian::read_u16(&rest[1..3]) as usize;
+        let value = match rest[3..].get(..length) {
+            Some(value) => value,
+            None => {
+                issues.push(Issue::error(unexpected_eof().at(
+                    offset + 3,
+                    Some(iei),
+                    "value",
+                )));
+                break;
+            }
+        };
+
+        // A non-numeric IMEI is reported on its own, so the rest of the header is still read.
+        let information_element =
+            match InformationElementRef::from_value(iei, value, ParseOptions::lenient()) {
+                Err(err @ Error::InvalidImei(_)) => {
+                    issues.push(Issue::error(value_error(err, iei, offset)));
+                    InformationElementRef::from_value(
+                        iei,
+                        value,
+                        ParseOptions::lenient().skip_imei_check(),
+                    )
+                }
+                result => result,
+            };
+        match information_element {
+            Ok(information_element) => {
+                let strict = ParseOptions::strict().skip_imei_check();
+                if let Err(err) = InformationElementRef::from_value(iei, value, strict) {
+                    issues.push(Issue::warning(value_error(err, iei, offset)));
+                }
+                check_value(&information_element, iei, offset, issues);
+                information_elements.push(Located {
+                    offset,
+                    iei,
+                    information_element: information_element.to_owned(),
+                });
+            }
+            Err(err) => issues.push(Issue::error(value_error(err, iei, offset))),
+        }
+        offset += 3 + length;
+    }
+
+    if data.len() > end {
+        issues.push(Issue::warning(Error::TrailingBytes(data.len() - end).at(
+            end,
+            None,
+            "trailing_bytes",
+        )));
+    }
+    Some(information_elements)
+}
+
+/// Checks what the `DirectIP` documentation does not forbid outright, but is most likely wrong.
+fn check_value(
+    information_element: &InformationElementRef<'_>,
+    iei: u8,
+    offset: usize,
+    issues: &mut Vec<Issue>,
+) {
+    match information_element {
+        InformationElementRef::Unknown { .. } => issues.push(Issue::warning(
+            Error::InvalidInformationElementIdentifier(iei).at(offset, Some(iei), "iei"),
+        )),
+        InformationElementRef::Header(header) => {
+            // Gateways usually send `0` instead of the check digit, but a numeric IMEI with
+            // another wrong digit is worth a look.
+            let imei = *header.imei();
+            if imei.is_numeric() && imei[14] != b'0' && imei.validate().is_err() {
+                issues.push(Issue::warning(value_error(
+                    Error::InvalidImei(imei),
+                    iei,
+                    offset,
+                )));
+            }
+            // The time of session is unsigned, so a negative timestamp written as a signed one
+            // reads as a time past 2038. It is reported as the negative timestamp it most likely
+            // is.
+            if let Header::MOHeader(header) = header {
+                let timestamp = header.time_of_session.unix_timestamp();
+                if timestamp > i64::from(i32::MAX) {
+                    let signed = i64::from(timestamp as u32 as i32);
+                    issues.push(Issue::warning(Error::NegativeTimestamp(signed).at(
+                        offset + 27,
+                        Some(iei),
+                        "time_of_session",
+                    )));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Builds the best message possible, reporting duplicates and direction mismatches.
+fn assemble(information_elements: Vec<Located>, issues: &mut Vec<Issue>) -> Option<Message> {
+    let mobile_originated =
+        information_elements
+            .iter()
+            .find_map(|located| match located.information_element {
+                InformationElement::Header(Header::MOHeader(_)) => Some(true),
+                InformationElement::Header(Header::MTHeader(_)) => Some(false),
+                _ => None,
+            });
+    let mobile_originated = match mobile_originated {
+        Some(mobile_originated) => mobile_originated,
+        None => {
+            // A lone confirmation is a complete `DirectIP` message without any header.
+            let confirmation = matches!(
+                information_elements.as_slice(),
+                [Located {
+                    information_element: InformationElement::Status(_),
+                    ..
+                }]
+            );
+            if !confirmation {
+                issues.push(Issue::error(Error::NoHeader));
+            }
+            return None;
+        }
+    };
+
+    let mut header = None;
+    let mut payload = None;
+    let mut location = None;
+    let mut priority = None;
+    let mut others = Vec::new();
+    let mut order = Vec::new();
+    for Located {
+        offset,
+        iei,
+        information_element,
+    } in information_elements
+    {
+        let misplaced = |err: Error| Issue::error(err.at(offset, Some(iei), "iei"));
+        if is_mobile_originated(iei) == Some(!mobile_originated) {
+            issues.push(misplaced(Error::DirectionMismatch {
+                iei,
+                mobile_originated,
+            }));
+            continue;
+        }
+        let slot = match information_element {
+            InformationElement::Header(h) if header.is_none() => {
+                header = Some(h);
+                IeSlot::Header
+            }
+            InformationElement::Header(_) => {
+                issues.push(misplaced(Error::TwoHeaders));
+                continue;
+            }
+            InformationElement::MOPayload(p) | InformationElement::MTPayload(p)
+                if payload.is_none() =>
+            {
+                payload = Some(p);
+                IeSlot::Payload
+            }
+            InformationElement::MOPayload(_) | InformationElement::MTPayload(_) => {
+                issues.push(misplaced(Error::TwoPayloads));
+                continue;
+            }
+            InformationElement::LocationInformation(l) if location.is_none() => {
+                location = Some(l);
+                IeSlot::Location
+            }
+            InformationElement::LocationInformation(_) => {
+                issues.push(misplaced(Error::TwoLocations));
+                continue;
+            }
+            InformationElement::Priority(p) if priority.is_none() => {
+                priority = Some(p);
+                IeSlot::Priority
+            }
+            InformationElement::Priority(_) => {
+                issues.push(misplaced(Error::TwoPriorities));
+                continue;
+            }
+            ie => {
+                others.push(ie);
+                IeSlot::Other(others.len() - 1)
+            }
+        };
+        order.push(slot);
+    }
+
+    let header = header?;
+    let payload_required = match header {
+        Header::MOHeader(_) => false,
+        Header::MTHeader(header) => header.flags.requires_payload(),
+    };
+    if payload_required && payload.is_none() {
+        issues.push(Issue::error(Error::NoPayload));
+    }
+    Some(Message::from_parts(
+        header, payload, location, priority, others, order,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mo;
+
+    fn summary(report: &ValidationReport) -> Vec<(Severity, Option<usize>, String)> {
+        report
+            .issues()
+            .iter()
+            .map(|issue| {
+                (
+                    issue.severity(),
+                    issue.offset(),
+                    issue.as_error().root().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fixtures() {
+        for path in &[
+            "data/0-mo.sbd",
+            "data/1-mo-location.sbd",
+            "data/iridium.ack",
+            "data/resp.sbd",
+        ] {
+            let report = validate(&std::fs::read(path).unwrap());
+            assert!(report.issues().is_empty(), "{}: {}", path, report);
+        }
+        let report = validate(&std::fs::read("data/data.sbd").unwrap());
+        let data = std::fs::read("data/data.sbd").unwrap();
+        assert_eq!(
+            vec![(
+                Severity::Warning,
+                Some(data.len() - 1),
+                "1 bytes follow the end of the message".to_string()
+            )],
+            summary(&report)
+        );
+        assert_eq!(
+            Message::from_path("data/data.sbd").unwrap(),
+            report.into_message().unwrap()
+        );
+    }
+
+    #[test]
+    fn collects_every_issue() {
+        let mut data = std::fs::read("data/1-mo-location.sbd").unwrap();
+        data[25] = 3;
+        data.extend_from_slice(&[0x02, 0, 1, b'x']);
+        data.extend_from_slice(&[0x99, 0, 0]);
+        data.extend_from_slice(&[0x46, 0, 2, 0, 1]);
+        data.extend_from_slice(&[0x05, 0, 0]);
+        let length = (data.len() - 3) as u16;
+        BigEndian::write_u16(&mut data[1..3], length);
+        data.push(0);
+
+        let report = validate(&data);
+        assert_eq!(
+            vec![
+                (
+                    Severity::Warning,
+                    Some(25),
+                    "unknown session status 3".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    Some(60),
+                    "invalid information element identifier 0x99".to_string()
+                ),
+                (
+                    Severity::Error,
+                    Some(69),
+                    "information element 0x05 declares 0 bytes but requires 1".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    Some(71),
+                    "1 bytes follow the end of the message".to_string()
+                ),
+                (
+                    Severity::Error,
+                    Some(56),
+                    "the message has two payloads".to_string()
+                ),
+                (
+                    Severity::Error,
+                    Some(63),
+                    "information element 0x46 cannot be part of a mobile-originated message"
+                        .to_string()
+                ),
+            ],
+            summary(&report)
+        );
+        assert!(!report.is_valid());
+        assert_eq!(3, report.errors().count());
+
+        let message = report.into_message().unwrap();
+        assert_eq!(b"hello", message.payload());
+        assert!(message.location().is_some());
+        assert_eq!(None, message.priority());
+        assert_eq!(
+            mo::SessionStatus::from(3),
+            message.header().as_mo().unwrap().session_status
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let data = std::fs::read("data/1-mo-location.sbd").unwrap();
+        let report = validate(&data[..50]);
+        assert_eq!(
+            vec![(
+                Severity::Error,
+                Some(49),
+                "I/O error: unexpected end of file".to_string()
+            )],
+            summary(&report)
+        );
+        let message = report.into_message().unwrap();
+        assert!(message.location().is_some());
+        assert!(message.payload().is_empty());
+
+        assert!(validate(&[]).message().is_none());
+        let report = validate(&[2, 0, 0]);
+        assert!(matches!(
+            report.issues()[0].as_error().root(),
+            Error::InvalidProtocolRevisionNumber(2)
+        ));
+    }
+
+    #[test]
+    fn missing_header_and_payload() {
+        let report = validate(&[1, 0, 4, 0x02, 0, 1, b'x']);
+        assert_eq!(
+            vec![(
+                Severity::Error,
+                None,
+                "the message has no header".to_string()
+            )],
+            summary(&report)
+        );
+        assert!(report.message().is_none());
+
+        let header = crate::mt::Header {
+            message_id: 1,
+            imei: (*b"300234063904190").into(),
+            flags: crate::mt::DispositionFlags::empty(),
+        };
+        let mut data = vec![1, 0, 24];
+        InformationElement::from(header)
+            .write_to(&mut data)
+            .unwrap();
+        let report = validate(&data);
+        assert_eq!(
+            vec![(
+                Severity::Error,
+                None,
+                "the message has no payload".to_string()
+            )],
+            summary(&report)
+        );
+        assert!(report.message().is_some());
+
+        data[26] = 0x01;
+        let report = validate(&data);
+        assert!(report.issues().is_empty(), "{}", report);
+        assert!(!report.message().unwrap().has_payload());
+    }
+
+    #[test]
+    fn negative_timestamp() {
+        let mut data = std::fs::read("data/0-mo.sbd").unwrap();
+        BigEndian::write_i32(&mut data[30..34], -1);
+        let report = validate(&data);
+        assert_eq!(
+            vec![(
+                Severity::Warning,
+                Some(30),
+                "the timestamp -1 is before 1970, which cannot be encoded".to_string()
+            )],
+            summary(&report)
+        );
+        assert!(report.is_valid());
+
+        BigEndian::write_i32(&mut data[30..34], i32::MAX);
+        assert!(validate(&data).issues().is_empty());
+    }
+
+    #[test]
+    fn imei() {
+        let mut data = std::fs::read("data/0-mo.sbd").unwrap();
+        data[23] = b'x';
+        let report = validate(&data);
+        assert_eq!(
+            vec![(
+                Severity::Error,
+                Some(10),
+                "invalid IMEI \"3002340639041x0\"".to_string()
+            )],
+            summary(&report)
+        );
+        assert!(!report.is_valid());
+        let message = report.into_message().unwrap();
+        assert_eq!("3002340639041x0", message.imei());
+        assert_eq!(b"test message from pete", message.payload());
+
+        data[23] = b'9';
+        data[24] = b'5';
+        let report = validate(&data);
+        assert_eq!(
+            vec![(
+                Severity::Warning,
+                Some(10),
+                "invalid IMEI \"300234063904195\"".to_string()
+            )],
+            summary(&report)
+        );
+        assert!(report.is_valid());
+    }
+}